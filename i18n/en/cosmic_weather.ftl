@@ -14,3 +14,137 @@ latitude = Latitude
 longitude = Longitude
 latitude-placeholder = Latitude (e.g., 40.7128)
 longitude-placeholder = Longitude (e.g., -74.0060)
+provider = Provider
+feels-like = Feels like { $value }
+humidity = Humidity: { $value }%
+uv-index = UV index: { $value }
+next-24-hours = Next 24 hours
+daily-forecast = Daily forecast
+today = Today
+weekday-mon = Mon
+weekday-tue = Tue
+weekday-wed = Wed
+weekday-thu = Thu
+weekday-fri = Fri
+weekday-sat = Sat
+weekday-sun = Sun
+dew-point = Dew point: { $value }
+wind-gust = Wind gusts: { $value }
+cloud-layers = Clouds: low { $low }, medium { $medium }, high { $high }
+met-complete = Detailed MET forecast
+stale-since = Stale since { $time }
+error-network = Couldn't reach the weather service. Check your internet connection.
+error-timeout = The weather service took too long to answer. Trying again later.
+error-forbidden = The weather service refused the request (status { $status }). Check the provider settings.
+error-rate-limited = Too many requests to the weather service. Waiting before trying again.
+error-server = The weather service is having problems (status { $status }). Trying again later.
+error-http = The weather service answered with status { $status }.
+error-parse = The weather service sent data this version can't read. An update may be needed.
+error-no-data = The weather service has no data for this location.
+loading-weather = Loading weather...
+no-weather-data = No weather data available
+retry-attempt = Retry attempt { $attempt } of { $max }
+city-placeholder = Search for a city
+location = Location
+add-location = Add location
+remove-location = Remove location
+new-location = New location
+compare-locations = Compare locations
+back = Back
+auto-location = Detect location automatically
+wind = Wind: { $value }, force { $force }
+pressure = Pressure: { $value }
+units-metric = Metric (°C, m/s, hPa, mm)
+units-imperial = Imperial (°F, mph, inHg, in)
+units-kelvin = Kelvin (K, m/s, hPa, mm)
+unit-default = Default
+temperature-unit = Temperature
+wind-speed-unit = Wind speed
+pressure-unit = Pressure
+precipitation-unit = Precipitation
+error-not-a-number = Enter a number, e.g. -23.5505
+error-out-of-range = Must be between { $min } and { $max }
+update-interval = Update interval
+adaptive-update = Adaptive updates
+minutes = { $count } min
+details = Details
+absolute-humidity = Absolute humidity: { $value } g/m³
+cloud-base = Cloud base: about { $value }
+wind-from = Wind: { $value } from { $direction }, force { $force }
+pressure-trend = Pressure: { $value }, forecast to { $trend ->
+    [rising] rise
+    [falling] fall
+   *[steady] stay steady
+}
+compass = { $point ->
+   *[0] N
+    [1] NNE
+    [2] NE
+    [3] ENE
+    [4] E
+    [5] ESE
+    [6] SE
+    [7] SSE
+    [8] S
+    [9] SSW
+    [10] SW
+    [11] WSW
+    [12] W
+    [13] WNW
+    [14] NW
+    [15] NNW
+}
+symbol-clearsky = Clear sky
+symbol-fair = Fair
+symbol-partlycloudy = Partly cloudy
+symbol-cloudy = Cloudy
+symbol-fog = Fog
+symbol-lightrainshowers = Light rain showers
+symbol-lightrainshowersandthunder = Light rain showers and thunder
+symbol-lightrain = Light rain
+symbol-lightrainandthunder = Light rain and thunder
+symbol-rainshowers = Rain showers
+symbol-rainshowersandthunder = Rain showers and thunder
+symbol-rain = Rain
+symbol-rainandthunder = Rain and thunder
+symbol-heavyrainshowers = Heavy rain showers
+symbol-heavyrainshowersandthunder = Heavy rain showers and thunder
+symbol-heavyrain = Heavy rain
+symbol-heavyrainandthunder = Heavy rain and thunder
+symbol-lightsleetshowers = Light sleet showers
+symbol-lightsleetshowersandthunder = Light sleet showers and thunder
+symbol-lightsleet = Light sleet
+symbol-lightsleetandthunder = Light sleet and thunder
+symbol-sleetshowers = Sleet showers
+symbol-sleetshowersandthunder = Sleet showers and thunder
+symbol-sleet = Sleet
+symbol-sleetandthunder = Sleet and thunder
+symbol-heavysleetshowers = Heavy sleet showers
+symbol-heavysleetshowersandthunder = Heavy sleet showers and thunder
+symbol-heavysleet = Heavy sleet
+symbol-heavysleetandthunder = Heavy sleet and thunder
+symbol-lightsnowshowers = Light snow showers
+symbol-lightsnowshowersandthunder = Light snow showers and thunder
+symbol-lightsnow = Light snow
+symbol-lightsnowandthunder = Light snow and thunder
+symbol-snowshowers = Snow showers
+symbol-snowshowersandthunder = Snow showers and thunder
+symbol-snow = Snow
+symbol-snowandthunder = Snow and thunder
+symbol-heavysnowshowers = Heavy snow showers
+symbol-heavysnowshowersandthunder = Heavy snow showers and thunder
+symbol-heavysnow = Heavy snow
+symbol-heavysnowandthunder = Heavy snow and thunder
+symbol-unknown = Unknown
+severity-extreme = Extreme danger
+severity-severe = Danger
+severity-moderate = Be aware
+severity-minor = Minor
+severity-unknown = Alert
+alert-onset = From { $time }
+alert-expires = Until { $time }
+api-key-placeholder = Your OpenWeatherMap API key
+error-missing-api-key = This provider needs an API key. Enter it in the settings.
+fallback-providers = When the provider fails, try
+data-source = Data from { $provider }
+data-source-fallback = Data from { $provider }, { $selected } is unavailable
@@ -13,4 +13,138 @@ auto-update = Atualização Automática
 latitude = Latitude
 longitude = Longitude
 latitude-placeholder = Latitude (ex: -29.1629)
-longitude-placeholder = Longitude (ex: -51.1833)
\ No newline at end of file
+longitude-placeholder = Longitude (ex: -51.1833)
+provider = Provedor
+feels-like = Sensação térmica de { $value }
+humidity = Umidade: { $value }%
+uv-index = Índice UV: { $value }
+next-24-hours = Próximas 24 horas
+daily-forecast = Previsão diária
+today = Hoje
+weekday-mon = Seg
+weekday-tue = Ter
+weekday-wed = Qua
+weekday-thu = Qui
+weekday-fri = Sex
+weekday-sat = Sáb
+weekday-sun = Dom
+dew-point = Ponto de orvalho: { $value }
+wind-gust = Rajadas de vento: { $value }
+cloud-layers = Nuvens: baixas { $low }, médias { $medium }, altas { $high }
+met-complete = Previsão MET detalhada
+stale-since = Desatualizado desde { $time }
+error-network = Não foi possível acessar o serviço de tempo. Verifique sua conexão com a internet.
+error-timeout = O serviço de tempo demorou demais para responder. Tentando novamente mais tarde.
+error-forbidden = O serviço de tempo recusou a requisição (status { $status }). Verifique as configurações do provedor.
+error-rate-limited = Muitas requisições ao serviço de tempo. Aguardando antes de tentar novamente.
+error-server = O serviço de tempo está com problemas (status { $status }). Tentando novamente mais tarde.
+error-http = O serviço de tempo respondeu com status { $status }.
+error-parse = O serviço de tempo enviou dados que esta versão não consegue ler. Pode ser necessária uma atualização.
+error-no-data = O serviço de tempo não tem dados para este local.
+loading-weather = Carregando o tempo...
+no-weather-data = Nenhum dado do tempo disponível
+retry-attempt = Tentativa { $attempt } de { $max }
+city-placeholder = Buscar uma cidade
+location = Local
+add-location = Adicionar local
+remove-location = Remover local
+new-location = Novo local
+compare-locations = Comparar locais
+back = Voltar
+auto-location = Detectar local automaticamente
+wind = Vento: { $value }, força { $force }
+pressure = Pressão: { $value }
+units-metric = Métrico (°C, m/s, hPa, mm)
+units-imperial = Imperial (°F, mph, inHg, in)
+units-kelvin = Kelvin (K, m/s, hPa, mm)
+unit-default = Padrão
+temperature-unit = Temperatura
+wind-speed-unit = Velocidade do vento
+pressure-unit = Pressão
+precipitation-unit = Precipitação
+error-not-a-number = Digite um número, por exemplo -23.5505
+error-out-of-range = Deve estar entre { $min } e { $max }
+update-interval = Intervalo de atualização
+adaptive-update = Atualizações adaptativas
+minutes = { $count } min
+details = Detalhes
+absolute-humidity = Umidade absoluta: { $value } g/m³
+cloud-base = Base das nuvens: cerca de { $value }
+wind-from = Vento: { $value } de { $direction }, força { $force }
+pressure-trend = Pressão: { $value }, previsão de { $trend ->
+    [rising] subida
+    [falling] queda
+   *[steady] estabilidade
+}
+compass = { $point ->
+   *[0] N
+    [1] NNE
+    [2] NE
+    [3] ENE
+    [4] L
+    [5] ESE
+    [6] SE
+    [7] SSE
+    [8] S
+    [9] SSO
+    [10] SO
+    [11] OSO
+    [12] O
+    [13] ONO
+    [14] NO
+    [15] NNO
+}
+symbol-clearsky = Céu limpo
+symbol-fair = Poucas nuvens
+symbol-partlycloudy = Parcialmente nublado
+symbol-cloudy = Nublado
+symbol-fog = Neblina
+symbol-lightrainshowers = Pancadas de chuva fracas
+symbol-lightrainshowersandthunder = Pancadas de chuva fracas e trovoada
+symbol-lightrain = Chuva fraca
+symbol-lightrainandthunder = Chuva fraca e trovoada
+symbol-rainshowers = Pancadas de chuva
+symbol-rainshowersandthunder = Pancadas de chuva e trovoada
+symbol-rain = Chuva
+symbol-rainandthunder = Chuva e trovoada
+symbol-heavyrainshowers = Pancadas de chuva fortes
+symbol-heavyrainshowersandthunder = Pancadas de chuva fortes e trovoada
+symbol-heavyrain = Chuva forte
+symbol-heavyrainandthunder = Chuva forte e trovoada
+symbol-lightsleetshowers = Pancadas de chuva com neve fracas
+symbol-lightsleetshowersandthunder = Pancadas de chuva com neve fracas e trovoada
+symbol-lightsleet = Chuva com neve fraca
+symbol-lightsleetandthunder = Chuva com neve fraca e trovoada
+symbol-sleetshowers = Pancadas de chuva com neve
+symbol-sleetshowersandthunder = Pancadas de chuva com neve e trovoada
+symbol-sleet = Chuva com neve
+symbol-sleetandthunder = Chuva com neve e trovoada
+symbol-heavysleetshowers = Pancadas de chuva com neve fortes
+symbol-heavysleetshowersandthunder = Pancadas de chuva com neve fortes e trovoada
+symbol-heavysleet = Chuva com neve forte
+symbol-heavysleetandthunder = Chuva com neve forte e trovoada
+symbol-lightsnowshowers = Pancadas de neve fracas
+symbol-lightsnowshowersandthunder = Pancadas de neve fracas e trovoada
+symbol-lightsnow = Neve fraca
+symbol-lightsnowandthunder = Neve fraca e trovoada
+symbol-snowshowers = Pancadas de neve
+symbol-snowshowersandthunder = Pancadas de neve e trovoada
+symbol-snow = Neve
+symbol-snowandthunder = Neve e trovoada
+symbol-heavysnowshowers = Pancadas de neve fortes
+symbol-heavysnowshowersandthunder = Pancadas de neve fortes e trovoada
+symbol-heavysnow = Neve forte
+symbol-heavysnowandthunder = Neve forte e trovoada
+symbol-unknown = Desconhecido
+severity-extreme = Perigo extremo
+severity-severe = Perigo
+severity-moderate = Atenção
+severity-minor = Menor
+severity-unknown = Alerta
+alert-onset = A partir de { $time }
+alert-expires = Até { $time }
+api-key-placeholder = Sua chave de API do OpenWeatherMap
+error-missing-api-key = Este provedor precisa de uma chave de API. Informe-a nas configurações.
+fallback-providers = Se o provedor falhar, tentar
+data-source = Dados de { $provider }
+data-source-fallback = Dados de { $provider }, { $selected } está indisponível
@@ -0,0 +1,352 @@
+// SPDX-License-Identifier: MIT
+
+//! Severe weather alerts from MET Norway's MetAlerts.
+//!
+//! The RSS feed lists current alerts, each linking to a CAP (Common Alerting
+//! Protocol) document with the affected area as polygons. The feed URL is
+//! configurable so a local stand-in can serve it.
+
+use crate::weather::{WeatherError, HTTP_CLIENT};
+use chrono::{DateTime, Local};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+
+/// Used when `Config::alerts_url` is not set.
+pub const DEFAULT_ALERTS_URL: &str = "https://api.met.no/weatherapi/metalerts/2.0/current.rss";
+
+/// CAP documents downloaded at the same time.
+const MAX_CONCURRENT_DOWNLOADS: usize = 4;
+
+/// How bad the weather is expected to get, mildest first. MET Norway issues
+/// moderate alerts as yellow, severe as orange and extreme as red.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Severity {
+    Minor,
+    Moderate,
+    Severe,
+    Extreme,
+    #[serde(other)]
+    Unknown,
+}
+
+impl Severity {
+    /// Order when sorting alerts, unknown counting as the mildest.
+    pub fn rank(self) -> u8 {
+        match self {
+            Self::Unknown => 0,
+            Self::Minor => 1,
+            Self::Moderate => 2,
+            Self::Severe => 3,
+            Self::Extreme => 4,
+        }
+    }
+}
+
+/// An alert that applies to a location.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Alert {
+    pub event: String,
+    pub headline: String,
+    pub description: String,
+    pub severity: Severity,
+    pub onset: Option<DateTime<Local>>,
+    pub expires: Option<DateTime<Local>>,
+    pub area: String,
+    /// Outlines of the affected area as (latitude, longitude) points.
+    pub polygons: Vec<Vec<(f64, f64)>>,
+}
+
+impl Alert {
+    /// Whether the point lies inside any polygon of the affected area.
+    pub fn covers(&self, lat: f64, lon: f64) -> bool {
+        self.polygons.iter().any(|polygon| contains(polygon, lat, lon))
+    }
+
+    pub fn is_expired(&self, now: DateTime<Local>) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Rss {
+    channel: Channel,
+}
+
+#[derive(Debug, Deserialize)]
+struct Channel {
+    #[serde(default, rename = "item")]
+    items: Vec<Item>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Item {
+    link: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CapAlert {
+    status: String,
+    msg_type: String,
+    #[serde(default, rename = "info")]
+    infos: Vec<CapInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CapInfo {
+    language: Option<String>,
+    event: String,
+    severity: Severity,
+    onset: Option<DateTime<Local>>,
+    expires: Option<DateTime<Local>>,
+    headline: Option<String>,
+    description: Option<String>,
+    #[serde(default, rename = "area")]
+    areas: Vec<CapArea>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CapArea {
+    area_desc: String,
+    #[serde(default, rename = "polygon")]
+    polygons: Vec<String>,
+}
+
+/// Alerts covering the coordinates that haven't expired yet, most severe first,
+/// in `language` where the alert is translated.
+pub async fn active_alerts(feed_url: String, lat: f64, lon: f64, language: String) -> Result<Vec<Alert>, WeatherError> {
+    // MET Norway already narrows the feed down, a stand-in may not
+    let lat_param = format!("{:.4}", lat);
+    let lon_param = format!("{:.4}", lon);
+    let feed = fetch_text(&feed_url, &[("lat", &lat_param), ("lon", &lon_param)]).await?;
+    let links = parse_feed(&feed)?;
+
+    let documents: Vec<Result<String, WeatherError>> = futures_util::stream::iter(links)
+        .map(|link| async move { fetch_text(&link, &[]).await })
+        .buffer_unordered(MAX_CONCURRENT_DOWNLOADS)
+        .collect()
+        .await;
+
+    Ok(collect_alerts(documents, lat, lon, &language, Local::now()))
+}
+
+// One missing or broken document shouldn't hide the other alerts
+fn collect_alerts(
+    documents: Vec<Result<String, WeatherError>>,
+    lat: f64,
+    lon: f64,
+    language: &str,
+    now: DateTime<Local>,
+) -> Vec<Alert> {
+    let mut alerts = Vec::new();
+    for document in documents {
+        let document = match document {
+            Ok(document) => document,
+            Err(err) => {
+                eprintln!("Error fetching alert: {}", err);
+                continue;
+            }
+        };
+        match parse_cap(&document, language) {
+            Ok(Some(alert)) if alert.covers(lat, lon) && !alert.is_expired(now) => alerts.push(alert),
+            Ok(_) => {}
+            Err(err) => eprintln!("Ignoring unreadable alert: {}", err),
+        }
+    }
+    alerts.sort_by(|a, b| b.severity.rank().cmp(&a.severity.rank()).then(a.onset.cmp(&b.onset)));
+    alerts
+}
+
+async fn fetch_text(url: &str, query: &[(&str, &str)]) -> Result<String, WeatherError> {
+    let response = HTTP_CLIENT.get(url).query(query).send().await?;
+    if response.status().is_success() {
+        Ok(response.text().await?)
+    } else {
+        Err(WeatherError::from_status(response.status(), response.headers()))
+    }
+}
+
+// Links to the CAP documents of the alerts in the feed
+fn parse_feed(xml: &str) -> Result<Vec<String>, WeatherError> {
+    let rss: Rss = quick_xml::de::from_str(xml)?;
+    Ok(rss.channel.items.into_iter().map(|item| item.link).collect())
+}
+
+// Reads a CAP document, `None` for cancellations, tests and other non-alerts
+fn parse_cap(xml: &str, language: &str) -> Result<Option<Alert>, WeatherError> {
+    let cap: CapAlert = quick_xml::de::from_str(xml)?;
+    if cap.status != "Actual" || cap.msg_type == "Cancel" {
+        return Ok(None);
+    }
+
+    // CAP defaults to English when no language is given
+    let info_language = |info: &CapInfo| primary_language(info.language.as_deref().unwrap_or("en-US"));
+    let mut infos = cap.infos;
+    let index = infos
+        .iter()
+        .position(|info| info_language(info) == primary_language(language))
+        .or_else(|| infos.iter().position(|info| info_language(info) == "en"));
+    let info = match index {
+        Some(index) => infos.swap_remove(index),
+        None if !infos.is_empty() => infos.swap_remove(0),
+        None => return Ok(None),
+    };
+
+    let polygons = info
+        .areas
+        .iter()
+        .flat_map(|area| &area.polygons)
+        .map(|polygon| parse_polygon(polygon))
+        .collect::<Result<Vec<_>, _>>()?;
+    let area = info
+        .areas
+        .iter()
+        .map(|area| area.area_desc.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    Ok(Some(Alert {
+        headline: info.headline.unwrap_or_else(|| info.event.clone()),
+        event: info.event,
+        description: info.description.unwrap_or_default(),
+        severity: info.severity,
+        onset: info.onset,
+        expires: info.expires,
+        area,
+        polygons,
+    }))
+}
+
+// The language without its region, e.g. "en" for "en-GB". MET Norway issues
+// alerts as "no", which covers both Bokmål and Nynorsk.
+fn primary_language(code: &str) -> String {
+    let primary = code.split(['-', '_']).next().unwrap_or_default().to_lowercase();
+    match primary.as_str() {
+        "nb" | "nn" => "no".to_string(),
+        _ => primary,
+    }
+}
+
+// CAP polygons are space separated "latitude,longitude" pairs
+fn parse_polygon(polygon: &str) -> Result<Vec<(f64, f64)>, WeatherError> {
+    polygon
+        .split_whitespace()
+        .map(|point| {
+            point
+                .split_once(',')
+                .and_then(|(lat, lon)| Some((lat.parse().ok()?, lon.parse().ok()?)))
+                .ok_or_else(|| WeatherError::Parse(format!("invalid polygon point {}", point)))
+        })
+        .collect()
+}
+
+// Even-odd ray casting, treating coordinates as planar, which is plenty for
+// areas the size of a county
+fn contains(polygon: &[(f64, f64)], lat: f64, lon: f64) -> bool {
+    let mut inside = false;
+    let mut previous = match polygon.last() {
+        Some(&point) => point,
+        None => return false,
+    };
+    for &(point_lat, point_lon) in polygon {
+        let (previous_lat, previous_lon) = previous;
+        if (point_lat > lat) != (previous_lat > lat) {
+            let crossing_lon =
+                point_lon + (lat - point_lat) / (previous_lat - point_lat) * (previous_lon - point_lon);
+            if lon < crossing_lon {
+                inside = !inside;
+            }
+        }
+        previous = (point_lat, point_lon);
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEED: &str = include_str!("../tests/fixtures/metalerts_feed.rss");
+    const CAP: &str = include_str!("../tests/fixtures/metalerts_cap.xml");
+
+    #[test]
+    fn feed_links_to_cap_documents() {
+        let links = parse_feed(FEED).unwrap();
+
+        assert_eq!(
+            links,
+            vec![
+                "https://api.met.no/weatherapi/metalerts/2.0/current?cap=2.49.0.1.578.0.20240312093012.001",
+                "https://api.met.no/weatherapi/metalerts/2.0/current?cap=2.49.0.1.578.0.20240312101544.004",
+            ]
+        );
+    }
+
+    #[test]
+    fn cap_alert_is_parsed_in_the_requested_language() {
+        let alert = parse_cap(CAP, "en").unwrap().unwrap();
+
+        assert_eq!(alert.event, "gale");
+        assert_eq!(alert.headline, "Gale warning, orange level, Vestland");
+        assert_eq!(alert.severity, Severity::Severe);
+        assert_eq!(alert.area, "Vestland");
+        assert_eq!(
+            alert.onset.unwrap().to_rfc3339(),
+            DateTime::parse_from_rfc3339("2024-03-12T15:00:00+01:00").unwrap().with_timezone(&Local).to_rfc3339()
+        );
+        assert!(alert.expires.unwrap() > alert.onset.unwrap());
+        assert_eq!(alert.polygons[0].len(), 5);
+
+        let norwegian = parse_cap(CAP, "no").unwrap().unwrap();
+        assert_eq!(norwegian.headline, "Kuling, oransje nivå, Vestland");
+
+        // Bokmål and Nynorsk get the Norwegian text
+        assert_eq!(parse_cap(CAP, "nb").unwrap().unwrap().headline, norwegian.headline);
+        assert_eq!(parse_cap(CAP, "nn-NO").unwrap().unwrap().headline, norwegian.headline);
+        assert_eq!(parse_cap(CAP, "en-US").unwrap().unwrap().headline, alert.headline);
+
+        // Languages the alert isn't issued in fall back to English
+        let portuguese = parse_cap(CAP, "pt").unwrap().unwrap();
+        assert_eq!(portuguese.headline, alert.headline);
+    }
+
+    #[test]
+    fn cancellations_are_skipped() {
+        let cancel = CAP.replace("<msgType>Alert</msgType>", "<msgType>Cancel</msgType>");
+        assert_eq!(parse_cap(&cancel, "en"), Ok(None));
+
+        let exercise = CAP.replace("<status>Actual</status>", "<status>Exercise</status>");
+        assert_eq!(parse_cap(&exercise, "en"), Ok(None));
+    }
+
+    #[test]
+    fn only_points_inside_the_area_are_covered() {
+        let alert = parse_cap(CAP, "en").unwrap().unwrap();
+
+        assert!(alert.covers(60.39, 5.32)); // Bergen
+        assert!(!alert.covers(59.91, 10.75)); // Oslo
+        assert!(!alert.covers(60.39, 3.0)); // west of the coast line
+    }
+
+    #[test]
+    fn broken_documents_dont_hide_other_alerts() {
+        let onset = parse_cap(CAP, "en").unwrap().unwrap().onset.unwrap();
+        let documents = vec![
+            Err(WeatherError::Server(503)),
+            Ok(CAP.to_string()),
+            Ok(CAP.replace("</alert>", "")),
+        ];
+
+        let alerts = collect_alerts(documents, 60.39, 5.32, "en", onset);
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].headline, "Gale warning, orange level, Vestland");
+    }
+
+    #[test]
+    fn malformed_polygons_are_rejected() {
+        assert!(parse_polygon("60.0,5.0 61.0").is_err());
+        assert_eq!(parse_polygon("60,5 61,6").unwrap(), vec![(60.0, 5.0), (61.0, 6.0)]);
+    }
+}
@@ -1,15 +1,73 @@
 // SPDX-License-Identifier: MIT
 
-use crate::config::Config;
+use crate::alerts::{self, Alert, Severity};
+use crate::config::{self, Config, CoordinateError, Location};
 use crate::fl;
-use crate::weather::{self, WeatherData};
+use crate::geoclue::{self, Position};
+use crate::geocoding::{self, Place};
+use crate::meteo::{self, PressureTrend};
+use crate::schedule;
+use crate::units::{PrecipitationUnit, PressureUnit, TemperatureUnit, UnitOverrides, Units, WindSpeedUnit};
+use crate::upower;
+use crate::weather::{self, Forecast, Provider, ProviderOptions, Retries, WeatherData, WeatherError, MAX_RETRY_ATTEMPTS};
 use cosmic::cosmic_config::{self, CosmicConfigEntry};
-use cosmic::iced::{window::Id, Limits, Subscription};
+use cosmic::iced::{window::Id, Color, Limits, Subscription};
 use cosmic::iced_winit::commands::popup::{destroy_popup, get_popup};
 use cosmic::prelude::*;
 use cosmic::widget;
+use futures_util::StreamExt;
+use std::collections::HashMap;
+use std::sync::LazyLock;
 use std::time::Duration;
 
+/// Pause in typing after which the city search runs.
+const SEARCH_DELAY: Duration = Duration::from_millis(400);
+
+/// Shown data older than this gets a "stale since" marker.
+const STALE_AFTER: Duration = Duration::from_secs(60 * 60);
+
+/// Presets of the update interval dropdown, in minutes.
+const UPDATE_INTERVALS: [u64; 5] = [5, 10, 15, 30, 60];
+
+/// Shortest fixed interval, in minutes, also for intervals set in the config file.
+const MIN_UPDATE_INTERVAL: u64 = 5;
+
+static UPDATE_INTERVAL_NAMES: LazyLock<Vec<String>> = LazyLock::new(|| {
+    UPDATE_INTERVALS
+        .iter()
+        .map(|minutes| fl!("minutes", count = *minutes))
+        .collect()
+});
+
+/// Display names for the provider dropdown, in `Provider::ALL` order.
+static PROVIDER_NAMES: LazyLock<Vec<&'static str>> =
+    LazyLock::new(|| Provider::ALL.iter().map(|p| p.name()).collect());
+
+/// Names for the units dropdown, in `Units::ALL` order.
+static UNIT_SYSTEM_NAMES: LazyLock<Vec<String>> =
+    LazyLock::new(|| vec![fl!("units-metric"), fl!("units-imperial"), fl!("units-kelvin")]);
+
+/// Choices of the per-quantity unit dropdowns: the system's default, then each unit.
+static TEMPERATURE_UNIT_NAMES: LazyLock<Vec<String>> =
+    LazyLock::new(|| unit_names(TemperatureUnit::ALL.map(TemperatureUnit::symbol)));
+static WIND_SPEED_UNIT_NAMES: LazyLock<Vec<String>> =
+    LazyLock::new(|| unit_names(WindSpeedUnit::ALL.map(WindSpeedUnit::symbol)));
+static PRESSURE_UNIT_NAMES: LazyLock<Vec<String>> =
+    LazyLock::new(|| unit_names(PressureUnit::ALL.map(PressureUnit::symbol)));
+static PRECIPITATION_UNIT_NAMES: LazyLock<Vec<String>> =
+    LazyLock::new(|| unit_names(PrecipitationUnit::ALL.map(PrecipitationUnit::symbol)));
+
+fn unit_names<const N: usize>(symbols: [&str; N]) -> Vec<String> {
+    std::iter::once(fl!("unit-default"))
+        .chain(symbols.iter().map(|symbol| symbol.to_string()))
+        .collect()
+}
+
+// Dropdown position of an overridden unit, 0 being the system's default
+fn override_index<T: PartialEq>(all: &[T], unit: Option<T>) -> Option<usize> {
+    Some(unit.and_then(|unit| all.iter().position(|u| *u == unit)).map_or(0, |i| i + 1))
+}
+
 /// The application model stores app-specific state used to describe its interface and
 /// drive its logic.
 #[derive(Default)]
@@ -20,12 +78,42 @@ pub struct AppModel {
     popup: Option<Id>,
     /// Configuration data that persists between application runs.
     config: Config,
-    /// Current weather data
-    weather_data: Option<WeatherData>,
+    /// Current weather data together with the hourly and daily forecast, per saved location
+    forecasts: HashMap<String, Forecast>,
+    /// Provider each forecast came from, per saved location
+    sources: HashMap<String, Provider>,
     /// Loading state
     loading: bool,
-    /// Error message if any
-    error: Option<String>,
+    /// Error of the last fetch, if it failed
+    error: Option<WeatherError>,
+    /// Time until the next automatic refresh, when the source sent `Expires`
+    refresh_interval: Option<Duration>,
+    /// Automatic retries made since the last successful or requested fetch
+    retries: Retries,
+    /// Places matching the city typed in the popup
+    city_results: Vec<Place>,
+    /// Incremented on every keystroke in the city field, to drop outdated searches
+    city_search: u64,
+    /// Place names looked up per saved location, `None` while the lookup runs
+    place_names: HashMap<String, Option<String>>,
+    /// Labels for the location switcher, in `config.locations` order
+    location_names: Vec<String>,
+    /// Whether the popup shows all saved locations side by side
+    comparing: bool,
+    /// Errors of the last compare fetch, per saved location
+    compare_errors: HashMap<String, WeatherError>,
+    /// Position from GeoClue the weather was last fetched for, in auto-detect mode
+    position: Option<Position>,
+    /// Coordinates of the active location as typed, which may not be valid yet
+    latitude_input: String,
+    longitude_input: String,
+    /// Why the typed coordinates weren't saved
+    latitude_error: Option<CoordinateError>,
+    longitude_error: Option<CoordinateError>,
+    /// Whether UPower reports running on battery, for the adaptive refresh
+    on_battery: bool,
+    /// Active severe weather alerts, per saved location
+    alerts: HashMap<String, Vec<Alert>>,
 }
 
 /// Messages emitted by the application and its widgets.
@@ -36,21 +124,221 @@ pub enum Message {
     SubscriptionChannel,
     UpdateConfig(Config),
     FetchWeather,
-    WeatherFetched(Result<WeatherData, String>),
+    RetryFetch(u32),
+    WeatherFetched((f64, f64), Result<(Provider, Forecast), WeatherError>),
+    AlertsFetched((f64, f64), Result<Vec<Alert>, WeatherError>),
     UpdateCity(String),
+    SearchCity(u64),
+    CitySearchResults(u64, Result<Vec<Place>, WeatherError>),
+    SelectPlace(usize),
+    PlaceResolved((f64, f64), Result<String, WeatherError>),
+    SelectLocation(usize),
+    AddLocation,
+    RemoveLocation,
+    ShowCompare(bool),
+    PositionUpdated(Position),
+    ToggleAutoLocation(bool),
+    CompareFetched(Vec<((f64, f64), Result<(Provider, Forecast), WeatherError>)>),
     UpdateApiKey(String),
     UpdateLatitude(String),
     UpdateLongitude(String),
     ToggleAutoUpdate(bool),
     UpdateInterval(u64),
-    UpdateUnits(String),
+    ToggleAdaptiveUpdate(bool),
+    PowerSourceChanged(bool),
+    UpdateUnits(Units),
+    UpdateUnitOverrides(UnitOverrides),
+    UpdateProvider(Provider),
+    ToggleFallback(Provider, bool),
+    RaiseFallback(Provider),
+    ToggleMetComplete(bool),
+}
+
+// Map weather condition to appropriate icon
+fn weather_icon_name(icon: &str) -> &'static str {
+    match icon {
+        "01d" | "01n" => "weather-clear-symbolic", // clear sky
+        "02d" | "02n" => "weather-few-clouds-symbolic", // few clouds
+        "03d" | "03n" => "weather-clouds-symbolic", // scattered clouds
+        "04d" | "04n" => "weather-overcast-symbolic", // broken clouds
+        "09d" | "09n" => "weather-showers-symbolic", // shower rain
+        "10d" | "10n" => "weather-showers-symbolic", // rain
+        "11d" | "11n" => "weather-storm-symbolic", // thunderstorm
+        "13d" | "13n" => "weather-snow-symbolic", // snow
+        "50d" | "50n" => "weather-fog-symbolic", // mist
+        _ => "weather-severe-alert-symbolic",
+    }
 }
 
-// Helper function to fetch weather data
-async fn fetch_weather_data(lat: f64, lon: f64) -> Result<WeatherData, String> {
-    match weather::get_weather_data(lat, lon).await {
-        Ok(data) => Ok(data),
-        Err(e) => Err(e.to_string()),
+// Short localized name for the day of a daily forecast row
+fn weekday_name(date: chrono::NaiveDate) -> String {
+    use chrono::{Datelike, Weekday};
+
+    if date == chrono::Local::now().date_naive() {
+        return fl!("today");
+    }
+
+    match date.weekday() {
+        Weekday::Mon => fl!("weekday-mon"),
+        Weekday::Tue => fl!("weekday-tue"),
+        Weekday::Wed => fl!("weekday-wed"),
+        Weekday::Thu => fl!("weekday-thu"),
+        Weekday::Fri => fl!("weekday-fri"),
+        Weekday::Sat => fl!("weekday-sat"),
+        Weekday::Sun => fl!("weekday-sun"),
+    }
+}
+
+// Helper function to fetch weather data, falling back along `chain`
+async fn fetch_weather_data(
+    chain: Vec<Provider>,
+    options: ProviderOptions,
+    lat: f64,
+    lon: f64,
+) -> Result<(Provider, Forecast), WeatherError> {
+    weather::fetch_with_fallback(&chain, |provider| {
+        weather::get_weather_data(provider, options.clone(), lat, lon)
+    })
+    .await
+}
+
+// Fetch several locations with bounded parallelism, each with the fallbacks
+async fn fetch_all_weather_data(
+    chain: Vec<Provider>,
+    options: ProviderOptions,
+    locations: Vec<(f64, f64)>,
+) -> Vec<((f64, f64), Result<(Provider, Forecast), WeatherError>)> {
+    weather::fetch_each(locations, |lat, lon| {
+        fetch_weather_data(chain.clone(), options.clone(), lat, lon)
+    })
+    .await
+}
+
+// Positions reported by GeoClue on the system bus, for the auto-detect mode
+fn geoclue_positions() -> impl futures_util::Stream<Item = Message> {
+    cosmic::iced::stream::channel(4, |mut output| async move {
+        let positions = match zbus::Connection::system().await {
+            Ok(connection) => geoclue::positions(&connection).await,
+            Err(err) => Err(err),
+        };
+
+        match positions {
+            Ok(positions) => {
+                let mut positions = std::pin::pin!(positions);
+                while let Some(position) = positions.next().await {
+                    if output.try_send(Message::PositionUpdated(position)).is_err() {
+                        break;
+                    }
+                }
+            }
+            Err(err) => eprintln!("Error starting GeoClue: {}", err),
+        }
+    })
+}
+
+// Localized reason a typed coordinate was rejected
+fn coordinate_error_message(error: &CoordinateError) -> String {
+    match error {
+        CoordinateError::NotANumber => fl!("error-not-a-number"),
+        CoordinateError::OutOfRange { min, max } => {
+            fl!("error-out-of-range", min = min.to_string(), max = max.to_string())
+        }
+    }
+}
+
+// Power source changes reported by UPower on the system bus, for the adaptive refresh
+fn power_source_changes() -> impl futures_util::Stream<Item = Message> {
+    cosmic::iced::stream::channel(4, |mut output| async move {
+        let changes = match zbus::Connection::system().await {
+            Ok(connection) => upower::on_battery_changes(&connection).await,
+            Err(err) => Err(err),
+        };
+
+        match changes {
+            Ok(changes) => {
+                let mut changes = std::pin::pin!(changes);
+                while let Some(on_battery) = changes.next().await {
+                    if output.try_send(Message::PowerSourceChanged(on_battery)).is_err() {
+                        break;
+                    }
+                }
+            }
+            Err(err) => eprintln!("Error watching the power source: {}", err),
+        }
+    })
+}
+
+// A coordinate field with the reason its input was rejected below it
+fn coordinate_input<'a>(
+    input: impl Into<Element<'a, Message>>,
+    error: Option<&CoordinateError>,
+) -> Element<'a, Message> {
+    let mut column = widget::column().push(input).spacing(4);
+    if let Some(error) = error {
+        column = column.push(widget::text::caption(coordinate_error_message(error)));
+    }
+    column.into()
+}
+
+// Key of a location in the per-location state, stable across small float differences
+fn location_key(lat: f64, lon: f64) -> String {
+    format!("{:.4},{:.4}", lat, lon)
+}
+
+// Banner for an alert, colored like MET Norway's yellow, orange and red levels
+fn alert_banner<'a>(alert: &Alert) -> Element<'a, Message> {
+    let (background, text) = match alert.severity {
+        Severity::Extreme => (Color::from_rgb8(0xd0, 0x21, 0x1c), Color::WHITE),
+        Severity::Severe => (Color::from_rgb8(0xf2, 0x8c, 0x1c), Color::BLACK),
+        Severity::Moderate => (Color::from_rgb8(0xf7, 0xd0, 0x2a), Color::BLACK),
+        Severity::Minor | Severity::Unknown => (Color::from_rgb8(0xc6, 0xd8, 0xe8), Color::BLACK),
+    };
+    let severity = match alert.severity {
+        Severity::Extreme => fl!("severity-extreme"),
+        Severity::Severe => fl!("severity-severe"),
+        Severity::Moderate => fl!("severity-moderate"),
+        Severity::Minor => fl!("severity-minor"),
+        Severity::Unknown => fl!("severity-unknown"),
+    };
+    let time = |time: chrono::DateTime<chrono::Local>| time.format("%d/%m %H:%M").to_string();
+
+    let mut column = widget::column()
+        .push(widget::text::heading(format!("{}: {}", severity, alert.headline)))
+        .spacing(4);
+    if let Some(onset) = alert.onset {
+        column = column.push(widget::text::caption(fl!("alert-onset", time = time(onset))));
+    }
+    if let Some(expires) = alert.expires {
+        column = column.push(widget::text::caption(fl!("alert-expires", time = time(expires))));
+    }
+
+    widget::container(column)
+        .padding(10)
+        .width(cosmic::iced::Length::Fill)
+        .class(cosmic::theme::Container::custom(move |_theme| cosmic::iced::widget::container::Style {
+            background: Some(background.into()),
+            text_color: Some(text),
+            border: cosmic::iced::Border {
+                radius: 8.0.into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }))
+        .into()
+}
+
+// Localized, actionable description of a failed fetch
+fn error_message(error: &WeatherError) -> String {
+    match error {
+        WeatherError::Network(_) => fl!("error-network"),
+        WeatherError::Timeout => fl!("error-timeout"),
+        WeatherError::Forbidden(status) => fl!("error-forbidden", status = status.to_string()),
+        WeatherError::RateLimited { .. } => fl!("error-rate-limited"),
+        WeatherError::Server(status) => fl!("error-server", status = status.to_string()),
+        WeatherError::Http(status) => fl!("error-http", status = status.to_string()),
+        WeatherError::Parse(_) => fl!("error-parse"),
+        WeatherError::NoData => fl!("error-no-data"),
+        WeatherError::MissingApiKey => fl!("error-missing-api-key"),
     }
 }
 
@@ -82,32 +370,56 @@ impl cosmic::Application for AppModel {
         _flags: Self::Flags,
     ) -> (Self, Task<cosmic::Action<Self::Message>>) {
         // Construct the app model with the runtime's core.
-        let config = cosmic_config::Config::new(Self::APP_ID, Config::VERSION)
-            .map(|context| match Config::get_entry(&context) {
-                Ok(config) => config,
-                Err((_errors, config)) => {
-                    config
+        let (config, migrated) = Config::load(Self::APP_ID);
+
+        // Save settings carried over from an older config version
+        if migrated {
+            if let Ok(helper) = cosmic_config::Config::new(Self::APP_ID, Config::VERSION) {
+                if let Err(err) = config.write_entry(&helper) {
+                    eprintln!("Error saving config: {}", err);
                 }
-            })
-            .unwrap_or_default();
+            }
+        }
 
-        let app = AppModel {
+        let mut app = AppModel {
             core,
             popup: None,
             config,
-            weather_data: None,
+            forecasts: HashMap::new(),
+            sources: HashMap::new(),
             loading: false,
             error: None,
+            refresh_interval: None,
+            retries: Retries::default(),
+            city_results: Vec::new(),
+            city_search: 0,
+            place_names: HashMap::new(),
+            location_names: Vec::new(),
+            comparing: false,
+            compare_errors: HashMap::new(),
+            position: None,
+            latitude_input: String::new(),
+            longitude_input: String::new(),
+            latitude_error: None,
+            longitude_error: None,
+            on_battery: false,
+            alerts: HashMap::new(),
         };
+        app.update_location_names();
+        app.sync_coordinate_inputs();
 
-        // Fetch weather data if coordinates are configured
-        let mut task = Task::none();
-        if let (Some(lat_str), Some(lon_str)) = (&app.config.latitude, &app.config.longitude) {
-            if let (Ok(lat), Ok(lon)) = (lat_str.parse::<f64>(), lon_str.parse::<f64>()) {
-                task = Task::perform(fetch_weather_data(lat, lon), Message::WeatherFetched).map(cosmic::Action::App);
+        // Show the last known forecasts until the network answers
+        let options = app.config.provider_options();
+        for (lat, lon) in app.config.locations.iter().filter_map(Location::coordinates) {
+            if let Some(forecast) = weather::cached_forecast(app.config.provider, &options, lat, lon) {
+                app.forecasts.insert(location_key(lat, lon), forecast);
+                app.sources.insert(location_key(lat, lon), app.config.provider);
             }
         }
 
+        // Fetch weather data if coordinates are configured
+        let task = app.update(Message::FetchWeather);
+
         (app, task)
     }
 
@@ -121,22 +433,10 @@ impl cosmic::Application for AppModel {
     /// This view should emit messages to toggle the applet's popup window, which will
     /// be drawn using the `view_window` method.
     fn view(&self) -> Element<'_, Self::Message> {
-        let icon_name = match &self.weather_data {
-            Some(weather) => {
-                // Map weather condition to appropriate icon
-                match weather.icon.as_str() {
-                    "01d" | "01n" => "weather-clear-symbolic", // clear sky
-                    "02d" | "02n" => "weather-few-clouds-symbolic", // few clouds
-                    "03d" | "03n" => "weather-clouds-symbolic", // scattered clouds
-                    "04d" | "04n" => "weather-overcast-symbolic", // broken clouds
-                    "09d" | "09n" => "weather-showers-symbolic", // shower rain
-                    "10d" | "10n" => "weather-showers-symbolic", // rain
-                    "11d" | "11n" => "weather-storm-symbolic", // thunderstorm
-                    "13d" | "13n" => "weather-snow-symbolic", // snow
-                    "50d" | "50n" => "weather-fog-symbolic", // mist
-                    _ => "weather-severe-alert-symbolic",
-                }
-            },
+        let units = self.config.unit_system();
+        let icon_name = match self.forecast().map(|f| &f.current) {
+            _ if self.alerts().next().is_some() => "weather-severe-alert-symbolic",
+            Some(weather) => weather_icon_name(&weather.icon),
             None => "weather-severe-alert-symbolic", // Default to alert icon when no weather data
         };
 
@@ -144,8 +444,8 @@ impl cosmic::Application for AppModel {
             .size(self.core.applet.suggested_size(true).0)
             .symbolic(true);
 
-        let temperature_text = match &self.weather_data {
-            Some(weather) => format!("{}°C", weather.temperature as i32),
+        let temperature_text = match self.forecast().map(|f| &f.current) {
+            Some(weather) => units.temperature(weather.temperature),
             None => {
                 if self.loading {
                     "...".to_string()
@@ -185,28 +485,163 @@ impl cosmic::Application for AppModel {
     /// multiple poups, you may match the id parameter to determine which popup to
     /// create a view for.
     fn view_window(&self, _id: Id) -> Element<'_, Self::Message> {
+        if self.comparing {
+            return self.core.applet.popup_container(self.view_compare()).into();
+        }
+
+        let units = self.config.unit_system();
+
         let mut content_list = widget::list_column()
             .padding(5)
             .spacing(10);
 
+        // Switch between the saved locations
+        if !self.config.auto_location && self.config.locations.len() > 1 {
+            content_list = content_list.add(widget::settings::item::builder(fl!("location")).control(
+                widget::dropdown(self.location_names.as_slice(),
+                    Some(self.config.active_location),
+                    Message::SelectLocation)
+            ));
+        }
+
+        // Severe weather alerts go first, colored by their level
+        for alert in self.alerts() {
+            content_list = content_list.add(alert_banner(alert));
+        }
+
         // Show weather data if available
-        if let Some(weather) = &self.weather_data {
-            let weather_info = widget::list_column()
+        if let Some(weather) = self.forecast().map(|f| &f.current) {
+            let mut weather_info = widget::list_column()
                 .padding(10)
                 .spacing(5)
-                .add(widget::text::title3(&weather.location))
-                .add(widget::text::heading(format!("{}°C", weather.temperature as i32)))
-                .add(widget::text(format!("Feels like {}°C", weather.feels_like as i32)))
-                .add(widget::text(&weather.description))
-                .add(widget::text(format!("Humidity: {}%", weather.humidity)));
+                .add(widget::text::title3(self.location_label(weather)))
+                .add(widget::text::heading(units.temperature(weather.temperature)))
+                .add(widget::text(fl!("feels-like", value = units.temperature(weather.feels_like))))
+                .add(widget::text(&weather.description));
+
+            if let Some(humidity) = weather.humidity {
+                weather_info = weather_info.add(widget::text(fl!("humidity", value = humidity)));
+            }
+            if let Some(uv_index) = weather.uv_index {
+                weather_info = weather_info.add(widget::text(fl!("uv-index", value = format!("{:.0}", uv_index))));
+            }
+            if let Some(layers) = weather.cloud_layers {
+                let percent = |value: Option<f64>| value.map_or("-".to_string(), |v| format!("{:.0}%", v));
+                weather_info = weather_info.add(widget::text(fl!(
+                    "cloud-layers",
+                    low = percent(layers.low),
+                    medium = percent(layers.medium),
+                    high = percent(layers.high)
+                )));
+            }
+
+            // Name the source, pointing out when a fallback stood in
+            if let Some(source) = self.source() {
+                let caption = if source == self.config.provider {
+                    fl!("data-source", provider = source.name())
+                } else {
+                    fl!("data-source-fallback", provider = source.name(), selected = self.config.provider.name())
+                };
+                weather_info = weather_info.add(widget::text::caption(caption));
+            }
+
+            // Mark data that couldn't be refreshed, e.g. while offline
+            if let Ok(age) = weather.timestamp.elapsed() {
+                if age > STALE_AFTER {
+                    let fetched_at = chrono::DateTime::<chrono::Local>::from(weather.timestamp);
+                    let format = if fetched_at.date_naive() == chrono::Local::now().date_naive() {
+                        "%H:%M"
+                    } else {
+                        "%d/%m %H:%M"
+                    };
+                    weather_info = weather_info.add(widget::text::caption(fl!(
+                        "stale-since",
+                        time = fetched_at.format(format).to_string()
+                    )));
+                }
+            }
+            if let Some(error) = &self.error {
+                weather_info = weather_info.add(widget::text::caption(error_message(error)));
+            }
 
-            content_list = content_list.add(weather_info);
+            content_list = content_list.add(weather_info).add(self.view_details(weather));
         } else if self.loading {
-            content_list = content_list.add(widget::text("Loading weather..."));
+            content_list = content_list.add(widget::text(fl!("loading-weather")));
         } else if let Some(error) = &self.error {
-            content_list = content_list.add(widget::text::body(format!("Error: {}", error)));
+            content_list = content_list.add(widget::text::body(error_message(error)));
         } else {
-            content_list = content_list.add(widget::text("No weather data available"));
+            content_list = content_list.add(widget::text(fl!("no-weather-data")));
+        }
+
+        if self.retries.attempt() > 0 {
+            content_list = content_list.add(widget::text::caption(fl!(
+                "retry-attempt",
+                attempt = self.retries.attempt(),
+                max = MAX_RETRY_ATTEMPTS
+            )));
+        }
+
+        // Show the next 24 hours as a horizontally scrolling strip
+        if let Some(forecast) = self.forecast().filter(|f| !f.hourly.is_empty()) {
+            let hours = forecast.next_hours(chrono::Local::now(), 24).fold(
+                widget::row()
+                    .spacing(16)
+                    .padding(cosmic::iced::Padding { top: 0.0, right: 0.0, bottom: 12.0, left: 0.0 }),
+                |row, hour| {
+                    let mut cell = widget::column()
+                        .push(widget::text::caption(hour.time.format("%H:%M").to_string()))
+                        .push(widget::icon::from_name(weather_icon_name(&hour.icon)).size(24).symbolic(true))
+                        .push(widget::text::body(units.temperature_short(hour.temperature)))
+                        .align_x(cosmic::iced::alignment::Horizontal::Center)
+                        .spacing(4);
+
+                    if hour.precipitation > 0.0 {
+                        cell = cell.push(widget::text::caption(units.precipitation(hour.precipitation)));
+                    }
+                    if let Some(probability) = hour.precipitation_probability {
+                        cell = cell.push(widget::text::caption(format!("{}%", probability)));
+                    }
+
+                    row.push(cell.push(widget::text::caption(units.wind_speed(hour.wind_speed))))
+                },
+            );
+
+            let hourly_section = widget::list_column()
+                .padding(10)
+                .spacing(5)
+                .add(widget::text::heading(fl!("next-24-hours")))
+                .add(widget::scrollable::horizontal(hours));
+
+            content_list = content_list.add(hourly_section);
+        }
+
+        // Show the daily summaries as a list
+        if let Some(forecast) = self.forecast().filter(|f| !f.daily.is_empty()) {
+            let mut daily_section = widget::list_column()
+                .padding(10)
+                .spacing(5)
+                .add(widget::text::heading(fl!("daily-forecast")));
+
+            for day in forecast.daily.iter().take(9) {
+                let mut row = widget::row()
+                    .push(widget::text::body(weekday_name(day.date)).width(cosmic::iced::Length::Fill))
+                    .push(widget::icon::from_name(weather_icon_name(&day.icon)).size(20).symbolic(true))
+                    .push(widget::text::body(format!(
+                        "{} / {}",
+                        units.temperature_short(day.temperature_max),
+                        units.temperature_short(day.temperature_min)
+                    )))
+                    .align_y(cosmic::iced::alignment::Vertical::Center)
+                    .spacing(12);
+
+                if day.precipitation > 0.0 {
+                    row = row.push(widget::text::caption(units.precipitation(day.precipitation)));
+                }
+
+                daily_section = daily_section.add(row);
+            }
+
+            content_list = content_list.add(daily_section);
         }
 
         // Add refresh button
@@ -215,40 +650,159 @@ impl cosmic::Application for AppModel {
 
         content_list = content_list.add(refresh_button);
 
-        // Add settings section
-        let settings_section = widget::list_column()
+        if self.config.locations.len() > 1 {
+            content_list = content_list.add(
+                widget::button::standard(fl!("compare-locations")).on_press(Message::ShowCompare(true))
+            );
+        }
+
+        // Add settings section, editing the active location
+        let location = self.config.active_location();
+        let mut location_buttons = widget::row()
+            .push(widget::button::standard(fl!("add-location")).on_press(Message::AddLocation))
+            .spacing(8);
+        if self.config.locations.len() > 1 {
+            location_buttons = location_buttons
+                .push(widget::button::standard(fl!("remove-location")).on_press(Message::RemoveLocation));
+        }
+
+        let mut settings_section = widget::list_column()
             .padding(10)
             .spacing(10)
-            .add(widget::settings::item::builder(fl!("latitude")).control(
-                widget::text_input(fl!("latitude-placeholder"), self.config.latitude.as_deref().unwrap_or(""))
-                    .on_input(|input| Message::UpdateLatitude(input))
+            .add(widget::settings::item::builder(fl!("auto-location")).control(
+                widget::toggler(self.config.auto_location).on_toggle(Message::ToggleAutoLocation)
+            ));
+
+        // Saved locations are only used while the position isn't detected
+        if !self.config.auto_location {
+            settings_section = settings_section
+                .add(location_buttons)
+                .add(widget::settings::item::builder(fl!("city")).control(
+                    widget::text_input(fl!("city-placeholder"), location.map_or("", |l| l.name.as_str()))
+                        .on_input(Message::UpdateCity)
+                ));
+
+            // Offer the places matching the typed city
+            for (i, place) in self.city_results.iter().enumerate() {
+                settings_section = settings_section.add(
+                    widget::button::text(place.display_name()).on_press(Message::SelectPlace(i))
+                );
+            }
+
+            settings_section = settings_section
+                .add(widget::settings::item::builder(fl!("latitude")).control(
+                    coordinate_input(
+                        widget::text_input(fl!("latitude-placeholder"), &self.latitude_input)
+                            .on_input(Message::UpdateLatitude),
+                        self.latitude_error.as_ref(),
+                    )
+                ))
+                .add(widget::settings::item::builder(fl!("longitude")).control(
+                    coordinate_input(
+                        widget::text_input(fl!("longitude-placeholder"), &self.longitude_input)
+                            .on_input(Message::UpdateLongitude),
+                        self.longitude_error.as_ref(),
+                    )
+                ));
+        }
+
+        let overrides = self.config.unit_overrides;
+        settings_section = settings_section
+            .add(widget::settings::item::builder(fl!("units")).control(
+                widget::dropdown(UNIT_SYSTEM_NAMES.as_slice(),
+                    Units::ALL.iter().position(|u| *u == self.config.units),
+                    |i| Message::UpdateUnits(Units::ALL[i]))
             ))
-            .add(widget::settings::item::builder(fl!("longitude")).control(
-                widget::text_input(fl!("longitude-placeholder"), self.config.longitude.as_deref().unwrap_or(""))
-                    .on_input(|input| Message::UpdateLongitude(input))
+            .add(widget::settings::item::builder(fl!("temperature-unit")).control(
+                widget::dropdown(TEMPERATURE_UNIT_NAMES.as_slice(),
+                    override_index(&TemperatureUnit::ALL, overrides.temperature),
+                    move |i| Message::UpdateUnitOverrides(UnitOverrides {
+                        temperature: i.checked_sub(1).map(|i| TemperatureUnit::ALL[i]),
+                        ..overrides
+                    }))
             ))
-            .add(widget::settings::item::builder(fl!("city")).control(
-                widget::text_input("", self.config.city.as_deref().unwrap_or(""))
-                    .on_input(Message::UpdateCity)
+            .add(widget::settings::item::builder(fl!("wind-speed-unit")).control(
+                widget::dropdown(WIND_SPEED_UNIT_NAMES.as_slice(),
+                    override_index(&WindSpeedUnit::ALL, overrides.wind_speed),
+                    move |i| Message::UpdateUnitOverrides(UnitOverrides {
+                        wind_speed: i.checked_sub(1).map(|i| WindSpeedUnit::ALL[i]),
+                        ..overrides
+                    }))
             ))
-            .add(widget::settings::item::builder(fl!("units")).control(
-                widget::dropdown(&["Celsius", "Fahrenheit"],
-                    match self.config.units.as_str() {
-                        "imperial" => Some(1),
-                        _ => Some(0), // default to Celsius
-                    },
-                    |i| {
-                        if i == 1 {
-                            Message::UpdateUnits("imperial".to_string())
-                        } else {
-                            Message::UpdateUnits("metric".to_string())
-                        }
-                    })
+            .add(widget::settings::item::builder(fl!("pressure-unit")).control(
+                widget::dropdown(PRESSURE_UNIT_NAMES.as_slice(),
+                    override_index(&PressureUnit::ALL, overrides.pressure),
+                    move |i| Message::UpdateUnitOverrides(UnitOverrides {
+                        pressure: i.checked_sub(1).map(|i| PressureUnit::ALL[i]),
+                        ..overrides
+                    }))
             ))
+            .add(widget::settings::item::builder(fl!("precipitation-unit")).control(
+                widget::dropdown(PRECIPITATION_UNIT_NAMES.as_slice(),
+                    override_index(&PrecipitationUnit::ALL, overrides.precipitation),
+                    move |i| Message::UpdateUnitOverrides(UnitOverrides {
+                        precipitation: i.checked_sub(1).map(|i| PrecipitationUnit::ALL[i]),
+                        ..overrides
+                    }))
+            ))
+            .add(widget::settings::item::builder(fl!("provider")).control(
+                widget::dropdown(PROVIDER_NAMES.as_slice(),
+                    Provider::ALL.iter().position(|p| *p == self.config.provider),
+                    |i| Message::UpdateProvider(Provider::ALL[i]))
+            ));
+
+        // Providers tried in turn when the selected one fails
+        settings_section = settings_section.add(widget::text::heading(fl!("fallback-providers")));
+        for (provider, enabled) in self.config.fallback_candidates() {
+            let mut control = widget::row().spacing(8).align_y(cosmic::iced::alignment::Vertical::Center);
+            if enabled && self.config.fallback_providers.first() != Some(&provider) {
+                control = control.push(
+                    widget::button::icon(widget::icon::from_name("go-up-symbolic"))
+                        .on_press(Message::RaiseFallback(provider))
+                );
+            }
+            control = control.push(
+                widget::toggler(enabled).on_toggle(move |enabled| Message::ToggleFallback(provider, enabled))
+            );
+            settings_section = settings_section.add(
+                widget::settings::item::builder(provider.name()).control(control)
+            );
+        }
+
+        if std::iter::once(&self.config.provider)
+            .chain(&self.config.fallback_providers)
+            .any(|provider| provider.needs_api_key())
+        {
+            settings_section = settings_section.add(widget::settings::item::builder(fl!("api-key")).control(
+                widget::secure_input(fl!("api-key-placeholder"), self.config.api_key.as_deref().unwrap_or(""), None, true)
+                    .on_input(Message::UpdateApiKey)
+            ));
+        }
+
+        settings_section = settings_section
             .add(widget::settings::item::builder(fl!("auto-update")).control(
                 widget::toggler(self.config.auto_update).on_toggle(Message::ToggleAutoUpdate)
             ));
 
+        if self.config.auto_update {
+            settings_section = settings_section.add(widget::settings::item::builder(fl!("adaptive-update")).control(
+                widget::toggler(self.config.adaptive_update).on_toggle(Message::ToggleAdaptiveUpdate)
+            ));
+        }
+        if self.config.auto_update && !self.config.adaptive_update {
+            settings_section = settings_section.add(widget::settings::item::builder(fl!("update-interval")).control(
+                widget::dropdown(UPDATE_INTERVAL_NAMES.as_slice(),
+                    UPDATE_INTERVALS.iter().position(|i| *i == self.config.update_interval),
+                    |i| Message::UpdateInterval(UPDATE_INTERVALS[i]))
+            ));
+        }
+
+        if self.config.provider == Provider::MetNorway {
+            settings_section = settings_section.add(widget::settings::item::builder(fl!("met-complete")).control(
+                widget::toggler(self.config.met_complete).on_toggle(Message::ToggleMetComplete)
+            ));
+        }
+
         content_list = content_list.add(settings_section);
 
         self.core.applet.popup_container(content_list).into()
@@ -272,13 +826,30 @@ impl cosmic::Application for AppModel {
                 }),
         ];
 
+        // Follow the position while the location is detected automatically
+        if self.config.auto_location {
+            subscriptions.push(Subscription::run(geoclue_positions));
+        }
+
+        // Slow down the adaptive refresh on battery
+        if self.config.auto_update && self.config.adaptive_update {
+            subscriptions.push(Subscription::run(power_source_changes));
+        }
+
         // Add periodic update subscription if auto-update is enabled
         if self.config.auto_update &&
-           self.config.latitude.is_some() &&
-           self.config.longitude.is_some() {
-            let update_interval = std::cmp::max(self.config.update_interval, 5); // Minimum 5 minutes
+           self.coordinates().is_some() {
+            let update_interval = if self.config.adaptive_update {
+                // Never before the source's `Expires`, there would be nothing new
+                schedule::adaptive_interval(self.forecast(), chrono::Local::now(), self.on_battery)
+                    .max(self.refresh_interval.unwrap_or_default())
+            } else {
+                // The chosen interval, but never before the source's `Expires`
+                Duration::from_secs(std::cmp::max(self.config.update_interval, MIN_UPDATE_INTERVAL) * 60)
+                    .max(self.refresh_interval.unwrap_or_default())
+            };
             subscriptions.push(
-                time::every(Duration::from_secs(update_interval * 60))
+                time::every(update_interval)
                     .map(|_| Message::FetchWeather)
             );
         }
@@ -298,6 +869,8 @@ impl cosmic::Application for AppModel {
             }
             Message::UpdateConfig(config) => {
                 self.config = config;
+                self.update_location_names();
+                self.sync_coordinate_inputs();
             }
             Message::TogglePopup => {
                 return if let Some(p) = self.popup.take() {
@@ -326,33 +899,115 @@ impl cosmic::Application for AppModel {
                 }
             }
             Message::FetchWeather => {
-                if let (Some(lat_str), Some(lon_str)) = (&self.config.latitude, &self.config.longitude) {
-                    if let (Ok(lat), Ok(lon)) = (lat_str.parse::<f64>(), lon_str.parse::<f64>()) {
-                        self.loading = true;
-                        self.error = None;
-                        return Task::perform(
-                            fetch_weather_data(lat, lon),
-                            Message::WeatherFetched
-                        ).map(cosmic::Action::App);
+                // A newly requested fetch gets the full number of retries
+                self.retries.reset();
+                return self.fetch_weather();
+            }
+            Message::WeatherFetched((lat, lon), result) => {
+                // Answers for a location switched away from only update its cache
+                let active = self.coordinates()
+                    .is_some_and(|coords| location_key(coords.0, coords.1) == location_key(lat, lon));
+                if !active {
+                    if let Ok((provider, forecast)) = result {
+                        self.forecasts.insert(location_key(lat, lon), forecast);
+                        self.sources.insert(location_key(lat, lon), provider);
                     }
+                    return Task::none();
                 }
-            }
-            Message::WeatherFetched(result) => {
+
                 self.loading = false;
                 match result {
-                    Ok(weather_data) => {
-                        self.weather_data = Some(weather_data);
+                    Ok((provider, forecast)) => {
+                        // Whole seconds keep the subscription stable between updates
+                        self.refresh_interval = forecast.expires.map(|expires| {
+                            let remaining = expires
+                                .duration_since(std::time::SystemTime::now())
+                                .unwrap_or_default();
+                            Duration::from_secs(remaining.as_secs().max(60))
+                        });
+                        self.forecasts.insert(location_key(lat, lon), forecast);
+                        self.sources.insert(location_key(lat, lon), provider);
                         self.error = None;
+                        self.retries.reset();
                     }
                     Err(e) => {
+                        // Retry transient failures with backoff, give up on the others
+                        let delay = self.retries.next(&e);
                         self.error = Some(e);
+
+                        if let Some(delay) = delay {
+                            let attempt = self.retries.attempt();
+                            return Task::perform(tokio::time::sleep(delay), move |_| {
+                                Message::RetryFetch(attempt)
+                            })
+                            .map(cosmic::Action::App);
+                        }
+                    }
+                }
+            }
+            Message::RetryFetch(attempt) => {
+                // Ignore retries made obsolete by a success in the meantime
+                // or a newly requested fetch
+                if attempt == self.retries.attempt() {
+                    return self.fetch_weather();
+                }
+            }
+            Message::ShowCompare(show) => {
+                self.comparing = show;
+                if show {
+                    let locations = self.config.locations.iter().filter_map(Location::coordinates).collect();
+                    return Task::perform(
+                        fetch_all_weather_data(self.config.provider_chain(), self.config.provider_options(), locations),
+                        Message::CompareFetched
+                    ).map(cosmic::Action::App);
+                }
+            }
+            Message::PositionUpdated(position) => {
+                // Small moves and GPS jitter keep the current forecast
+                if self.position.is_none_or(|last| last.moved_significantly(&position)) {
+                    self.position = Some(position);
+                    if self.config.auto_location {
+                        return self.update(Message::FetchWeather);
+                    }
+                }
+            }
+            Message::ToggleAutoLocation(enabled) => {
+                let mut config = self.config.clone();
+                config.auto_location = enabled;
+                self.config = config;
+                self.city_results.clear();
+                self.error = None;
+                self.refresh_interval = None;
+                self.retries.reset();
+
+                // Save the new configuration
+                if let Ok(helper) = cosmic::cosmic_config::Config::new(Self::APP_ID, Config::VERSION) {
+                    if let Err(err) = self.config.write_entry(&helper) {
+                        eprintln!("Error saving config: {}", err);
+                    }
+                }
+
+                return self.update(Message::FetchWeather);
+            }
+            Message::CompareFetched(results) => {
+                self.compare_errors.clear();
+                for ((lat, lon), result) in results {
+                    match result {
+                        Ok((provider, forecast)) => {
+                            self.forecasts.insert(location_key(lat, lon), forecast);
+                            self.sources.insert(location_key(lat, lon), provider);
+                        }
+                        Err(err) => {
+                            self.compare_errors.insert(location_key(lat, lon), err);
+                        }
                     }
                 }
             }
             Message::UpdateCity(city) => {
                 let mut config = self.config.clone();
-                config.city = Some(city);
+                config.active_location_mut().name = city;
                 self.config = config;
+                self.update_location_names();
 
                 // Save the new configuration
                 if let Ok(helper) = cosmic::cosmic_config::Config::new(Self::APP_ID, Config::VERSION) {
@@ -360,15 +1015,84 @@ impl cosmic::Application for AppModel {
                         eprintln!("Error saving config: {}", err);
                     }
                 }
+
+                // Search once typing pauses
+                self.city_search += 1;
+                let search = self.city_search;
+                return Task::perform(tokio::time::sleep(SEARCH_DELAY), move |_| Message::SearchCity(search))
+                    .map(cosmic::Action::App);
+            }
+            Message::SearchCity(search) => {
+                let query = self.config.active_location().map(|l| l.name.clone()).unwrap_or_default();
+                if search != self.city_search || query.trim().chars().count() < 2 {
+                    self.city_results.clear();
+                    return Task::none();
+                }
+
+                return Task::perform(
+                    geocoding::search(self.config.geocoding_url(), query.trim().to_string(), crate::i18n::language_code()),
+                    move |result| Message::CitySearchResults(search, result)
+                ).map(cosmic::Action::App);
+            }
+            Message::CitySearchResults(search, result) => {
+                if search == self.city_search {
+                    self.city_results = result.unwrap_or_else(|err| {
+                        eprintln!("Error searching city: {}", err);
+                        Vec::new()
+                    });
+                }
+            }
+            Message::AlertsFetched((lat, lon), result) => {
+                // Keep showing the previous alerts if the feed can't be read
+                match result {
+                    Ok(alerts) => {
+                        self.alerts.insert(location_key(lat, lon), alerts);
+                    }
+                    Err(err) => eprintln!("Error fetching weather alerts: {}", err),
+                }
             }
-            Message::UpdateApiKey(_api_key) => {
-                // In the MET Norway API, we don't need an API key
-                // But we keep this message for compatibility
+            Message::PlaceResolved((lat, lon), result) => {
+                match result {
+                    Ok(name) => {
+                        self.place_names.insert(location_key(lat, lon), Some(name));
+                    }
+                    Err(err) => {
+                        eprintln!("Error resolving place name: {}", err);
+                        // Look it up again on the next refresh
+                        self.place_names.remove(&location_key(lat, lon));
+                    }
+                }
             }
-            Message::UpdateLatitude(lat) => {
+            Message::SelectLocation(index) => {
                 let mut config = self.config.clone();
-                config.latitude = Some(lat);
+                if config.select_location(index) {
+                    self.config = config;
+                    self.sync_coordinate_inputs();
+                    self.city_results.clear();
+                    self.error = None;
+                    self.refresh_interval = None;
+                    self.retries.reset();
+
+                    // Save the new configuration
+                    if let Ok(helper) = cosmic::cosmic_config::Config::new(Self::APP_ID, Config::VERSION) {
+                        if let Err(err) = self.config.write_entry(&helper) {
+                            eprintln!("Error saving config: {}", err);
+                        }
+                    }
+
+                    return self.update(Message::FetchWeather);
+                }
+            }
+            Message::AddLocation => {
+                let mut config = self.config.clone();
+                config.add_location();
                 self.config = config;
+                self.update_location_names();
+                self.sync_coordinate_inputs();
+                self.city_results.clear();
+                self.error = None;
+                self.refresh_interval = None;
+                self.retries.reset();
 
                 // Save the new configuration
                 if let Ok(helper) = cosmic::cosmic_config::Config::new(Self::APP_ID, Config::VERSION) {
@@ -377,9 +1101,53 @@ impl cosmic::Application for AppModel {
                     }
                 }
             }
-            Message::UpdateLongitude(lon) => {
+            Message::RemoveLocation => {
                 let mut config = self.config.clone();
-                config.longitude = Some(lon);
+                if config.remove_active_location() {
+                    self.config = config;
+                    self.update_location_names();
+                    self.sync_coordinate_inputs();
+                    self.city_results.clear();
+                    self.error = None;
+                    self.refresh_interval = None;
+                    self.retries.reset();
+
+                    // Save the new configuration
+                    if let Ok(helper) = cosmic::cosmic_config::Config::new(Self::APP_ID, Config::VERSION) {
+                        if let Err(err) = self.config.write_entry(&helper) {
+                            eprintln!("Error saving config: {}", err);
+                        }
+                    }
+
+                    return self.update(Message::FetchWeather);
+                }
+            }
+            Message::SelectPlace(index) => {
+                if let Some(place) = self.city_results.get(index).cloned() {
+                    let mut config = self.config.clone();
+                    *config.active_location_mut() = Location {
+                        name: place.display_name(),
+                        latitude: Some(place.latitude),
+                        longitude: Some(place.longitude),
+                    };
+                    self.config = config;
+                    self.update_location_names();
+                    self.sync_coordinate_inputs();
+                    self.city_results.clear();
+
+                    // Save the new configuration
+                    if let Ok(helper) = cosmic::cosmic_config::Config::new(Self::APP_ID, Config::VERSION) {
+                        if let Err(err) = self.config.write_entry(&helper) {
+                            eprintln!("Error saving config: {}", err);
+                        }
+                    }
+
+                    return self.update(Message::FetchWeather);
+                }
+            }
+            Message::UpdateApiKey(api_key) => {
+                let mut config = self.config.clone();
+                config.api_key = Some(api_key).filter(|key| !key.is_empty());
                 self.config = config;
 
                 // Save the new configuration
@@ -389,6 +1157,45 @@ impl cosmic::Application for AppModel {
                     }
                 }
             }
+            Message::UpdateLatitude(input) => {
+                // Keep what was typed, but only save valid coordinates
+                let latitude = config::parse_latitude(&input);
+                self.latitude_input = input;
+                self.latitude_error = latitude.err();
+
+                if let Ok(latitude) = latitude {
+                    let mut config = self.config.clone();
+                    config.active_location_mut().latitude = latitude;
+                    self.config = config;
+                    self.update_location_names();
+
+                    // Save the new configuration
+                    if let Ok(helper) = cosmic::cosmic_config::Config::new(Self::APP_ID, Config::VERSION) {
+                        if let Err(err) = self.config.write_entry(&helper) {
+                            eprintln!("Error saving config: {}", err);
+                        }
+                    }
+                }
+            }
+            Message::UpdateLongitude(input) => {
+                let longitude = config::parse_longitude(&input);
+                self.longitude_input = input;
+                self.longitude_error = longitude.err();
+
+                if let Ok(longitude) = longitude {
+                    let mut config = self.config.clone();
+                    config.active_location_mut().longitude = longitude;
+                    self.config = config;
+                    self.update_location_names();
+
+                    // Save the new configuration
+                    if let Ok(helper) = cosmic::cosmic_config::Config::new(Self::APP_ID, Config::VERSION) {
+                        if let Err(err) = self.config.write_entry(&helper) {
+                            eprintln!("Error saving config: {}", err);
+                        }
+                    }
+                }
+            }
             Message::ToggleAutoUpdate(enabled) => {
                 let mut config = self.config.clone();
                 config.auto_update = enabled;
@@ -413,6 +1220,71 @@ impl cosmic::Application for AppModel {
                     }
                 }
             }
+            Message::UpdateUnitOverrides(overrides) => {
+                let mut config = self.config.clone();
+                config.unit_overrides = overrides;
+                self.config = config;
+
+                // Save the new configuration
+                if let Ok(helper) = cosmic::cosmic_config::Config::new(Self::APP_ID, Config::VERSION) {
+                    if let Err(err) = self.config.write_entry(&helper) {
+                        eprintln!("Error saving config: {}", err);
+                    }
+                }
+            }
+            Message::UpdateProvider(provider) => {
+                let mut config = self.config.clone();
+                config.provider = provider;
+                self.config = config;
+
+                // Save the new configuration
+                if let Ok(helper) = cosmic::cosmic_config::Config::new(Self::APP_ID, Config::VERSION) {
+                    if let Err(err) = self.config.write_entry(&helper) {
+                        eprintln!("Error saving config: {}", err);
+                    }
+                }
+
+                // Data from the previous source is no longer relevant
+                return self.update(Message::FetchWeather);
+            }
+            Message::ToggleMetComplete(enabled) => {
+                let mut config = self.config.clone();
+                config.met_complete = enabled;
+                self.config = config;
+
+                // Save the new configuration
+                if let Ok(helper) = cosmic::cosmic_config::Config::new(Self::APP_ID, Config::VERSION) {
+                    if let Err(err) = self.config.write_entry(&helper) {
+                        eprintln!("Error saving config: {}", err);
+                    }
+                }
+
+                return self.update(Message::FetchWeather);
+            }
+            Message::ToggleFallback(provider, enabled) => {
+                let mut config = self.config.clone();
+                config.set_fallback(provider, enabled);
+                self.config = config;
+
+                // Save the new configuration
+                if let Ok(helper) = cosmic::cosmic_config::Config::new(Self::APP_ID, Config::VERSION) {
+                    if let Err(err) = self.config.write_entry(&helper) {
+                        eprintln!("Error saving config: {}", err);
+                    }
+                }
+            }
+            Message::RaiseFallback(provider) => {
+                let mut config = self.config.clone();
+                config.raise_fallback(provider);
+                self.config = config;
+
+                // Save the new configuration
+                if let Ok(helper) = cosmic::cosmic_config::Config::new(Self::APP_ID, Config::VERSION) {
+                    if let Err(err) = self.config.write_entry(&helper) {
+                        eprintln!("Error saving config: {}", err);
+                    }
+                }
+            }
             Message::UpdateInterval(interval) => {
                 let mut config = self.config.clone();
                 config.update_interval = interval;
@@ -425,6 +1297,21 @@ impl cosmic::Application for AppModel {
                     }
                 }
             }
+            Message::ToggleAdaptiveUpdate(enabled) => {
+                let mut config = self.config.clone();
+                config.adaptive_update = enabled;
+                self.config = config;
+
+                // Save the new configuration
+                if let Ok(helper) = cosmic::cosmic_config::Config::new(Self::APP_ID, Config::VERSION) {
+                    if let Err(err) = self.config.write_entry(&helper) {
+                        eprintln!("Error saving config: {}", err);
+                    }
+                }
+            }
+            Message::PowerSourceChanged(on_battery) => {
+                self.on_battery = on_battery;
+            }
         }
         Task::none()
     }
@@ -432,4 +1319,217 @@ impl cosmic::Application for AppModel {
     fn style(&self) -> Option<cosmic::iced_runtime::Appearance> {
         Some(cosmic::applet::style())
     }
+}
+
+impl AppModel {
+    /// Coordinates to show the weather for: the detected position in auto-detect
+    /// mode, the active saved location otherwise.
+    fn coordinates(&self) -> Option<(f64, f64)> {
+        if self.config.auto_location {
+            return self.position.map(|p| (p.latitude, p.longitude));
+        }
+        self.config.active_location().and_then(Location::coordinates)
+    }
+
+    /// Fetches the forecast, alerts and place name for the active location.
+    fn fetch_weather(&mut self) -> Task<cosmic::Action<Message>> {
+        let Some((lat, lon)) = self.coordinates() else {
+            return Task::none();
+        };
+        self.loading = true;
+        self.error = None;
+        let provider = self.config.provider;
+        let fetch = Task::perform(
+            fetch_weather_data(self.config.provider_chain(), self.config.provider_options(), lat, lon),
+            move |result| Message::WeatherFetched((lat, lon), result)
+        ).map(cosmic::Action::App);
+        let mut tasks = vec![fetch];
+
+        // Sources with alerts of their own don't need MetAlerts
+        if !provider.has_alerts() {
+            tasks.push(Task::perform(
+                alerts::active_alerts(self.config.alerts_url(), lat, lon, crate::i18n::language_code()),
+                move |result| Message::AlertsFetched((lat, lon), result)
+            ).map(cosmic::Action::App));
+        }
+
+        // Look up a place name once per location
+        let key = location_key(lat, lon);
+        if !self.place_names.contains_key(&key) {
+            self.place_names.insert(key, None);
+            tasks.push(Task::perform(
+                geocoding::reverse(self.config.reverse_geocoding_url(), lat, lon, crate::i18n::language_code()),
+                move |result| Message::PlaceResolved((lat, lon), result)
+            ).map(cosmic::Action::App));
+        }
+        Task::batch(tasks)
+    }
+
+    /// Forecast for `coordinates`, if one was fetched or cached.
+    fn forecast(&self) -> Option<&Forecast> {
+        let (lat, lon) = self.coordinates()?;
+        self.forecasts.get(&location_key(lat, lon))
+    }
+
+    /// Provider the forecast for `coordinates` came from.
+    fn source(&self) -> Option<Provider> {
+        let (lat, lon) = self.coordinates()?;
+        self.sources.get(&location_key(lat, lon)).copied()
+    }
+
+    /// Alerts in effect at `coordinates`, from the provider if it has them and
+    /// from MetAlerts otherwise.
+    fn alerts(&self) -> impl Iterator<Item = &Alert> {
+        let met_alerts = self
+            .coordinates()
+            .filter(|_| !self.source().is_some_and(Provider::has_alerts))
+            .and_then(|(lat, lon)| self.alerts.get(&location_key(lat, lon)))
+            .into_iter()
+            .flatten();
+        met_alerts.chain(self.forecast().into_iter().flat_map(|f| &f.alerts))
+    }
+
+    /// Place name for the shown weather, falling back to the location's name and
+    /// then to the coordinates.
+    fn location_label(&self, weather: &WeatherData) -> String {
+        self.coordinates()
+            .and_then(|(lat, lon)| self.place_names.get(&location_key(lat, lon)).cloned().flatten())
+            .or_else(|| {
+                self.config
+                    .active_location()
+                    .filter(|_| !self.config.auto_location)
+                    .map(|l| l.name.clone())
+                    .filter(|name| !name.is_empty())
+            })
+            .unwrap_or_else(|| weather.location.clone())
+    }
+
+    /// Quantities derived from the current conditions, for those who want more
+    /// than the temperature.
+    fn view_details(&self, weather: &WeatherData) -> widget::ListColumn<'_, Message> {
+        let units = self.config.unit_system();
+        let mut details = widget::list_column()
+            .padding(10)
+            .spacing(5)
+            .add(widget::text::heading(fl!("details")));
+
+        if let Some(dew_point) = weather.dew_point {
+            details = details
+                .add(widget::text(fl!("dew-point", value = units.temperature(dew_point))))
+                .add(widget::text(fl!(
+                    "cloud-base",
+                    value = units.height(meteo::cloud_base(weather.temperature, dew_point))
+                )));
+        }
+        if let Some(humidity) = weather.humidity.filter(|humidity| *humidity > 0) {
+            details = details.add(widget::text(fl!(
+                "absolute-humidity",
+                value = format!("{:.1}", meteo::absolute_humidity(weather.temperature, f64::from(humidity)))
+            )));
+        }
+
+        if let Some(wind_speed) = weather.wind_speed {
+            let force = meteo::beaufort(wind_speed);
+            details = details.add(widget::text(match weather.wind_direction {
+                Some(direction) => fl!(
+                    "wind-from",
+                    value = units.wind_speed(wind_speed),
+                    direction = fl!("compass", point = meteo::compass_point(direction)),
+                    force = force
+                ),
+                None => fl!("wind", value = units.wind_speed(wind_speed), force = force),
+            }));
+        }
+        if let Some(wind_gust) = weather.wind_gust {
+            details = details.add(widget::text(fl!("wind-gust", value = units.wind_speed(wind_gust))));
+        }
+        if let Some(pressure) = weather.pressure {
+            details = details.add(widget::text(match weather.pressure_trend {
+                Some(trend) => fl!(
+                    "pressure-trend",
+                    value = units.pressure(pressure),
+                    trend = match trend {
+                        PressureTrend::Rising => "rising",
+                        PressureTrend::Steady => "steady",
+                        PressureTrend::Falling => "falling",
+                    }
+                ),
+                None => fl!("pressure", value = units.pressure(pressure)),
+            }));
+        }
+
+        details
+    }
+
+    /// All saved locations side by side, with their current and today's temperatures.
+    fn view_compare(&self) -> widget::ListColumn<'_, Message> {
+        let units = self.config.unit_system();
+        let mut content_list = widget::list_column()
+            .padding(5)
+            .spacing(10)
+            .add(widget::text::title3(fl!("compare-locations")));
+
+        for (location, name) in self.config.locations.iter().zip(&self.location_names) {
+            let key = location.coordinates().map(|(lat, lon)| location_key(lat, lon));
+            let forecast = key.as_ref().and_then(|key| self.forecasts.get(key));
+
+            let mut row = widget::row()
+                .push(widget::text::body(name.as_str()).width(cosmic::iced::Length::Fill))
+                .align_y(cosmic::iced::alignment::Vertical::Center)
+                .spacing(12);
+
+            if let Some(forecast) = forecast {
+                row = row
+                    .push(widget::icon::from_name(weather_icon_name(&forecast.current.icon)).size(20).symbolic(true))
+                    .push(widget::text::heading(units.temperature(forecast.current.temperature)));
+                if let Some(today) = forecast.today() {
+                    row = row.push(widget::text::caption(format!(
+                        "{} / {}",
+                        units.temperature_short(today.temperature_max),
+                        units.temperature_short(today.temperature_min)
+                    )));
+                }
+            } else if let Some(error) = key.as_ref().and_then(|key| self.compare_errors.get(key)) {
+                row = row.push(widget::text::caption(error_message(error)));
+            } else {
+                row = row.push(widget::text::caption("..."));
+            }
+
+            content_list = content_list.add(row);
+        }
+
+        content_list.add(widget::button::standard(fl!("back")).on_press(Message::ShowCompare(false)))
+    }
+
+    /// Shows the active location's coordinates in the input fields, unless what's
+    /// typed already means the same, e.g. "12." while typing "12.5".
+    fn sync_coordinate_inputs(&mut self) {
+        let location = self.config.active_location().cloned().unwrap_or_default();
+        if config::parse_latitude(&self.latitude_input) != Ok(location.latitude) {
+            self.latitude_input = location.latitude.map(|v| v.to_string()).unwrap_or_default();
+            self.latitude_error = None;
+        }
+        if config::parse_longitude(&self.longitude_input) != Ok(location.longitude) {
+            self.longitude_input = location.longitude.map(|v| v.to_string()).unwrap_or_default();
+            self.longitude_error = None;
+        }
+    }
+
+    /// Rebuilds the switcher labels after the saved locations changed.
+    fn update_location_names(&mut self) {
+        self.location_names = self
+            .config
+            .locations
+            .iter()
+            .map(|location| {
+                if !location.name.is_empty() {
+                    location.name.clone()
+                } else if let Some((lat, lon)) = location.coordinates() {
+                    format!("{:.2}, {:.2}", lat, lon)
+                } else {
+                    fl!("new-location")
+                }
+            })
+            .collect();
+    }
 }
\ No newline at end of file
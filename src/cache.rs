@@ -0,0 +1,93 @@
+// SPDX-License-Identifier: MIT
+
+//! Small JSON files kept under the XDG cache directory.
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::{Path, PathBuf};
+
+#[cfg(not(test))]
+fn cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("cosmic-weather"))
+}
+
+// Keep tests away from the user's cache
+#[cfg(test)]
+fn cache_dir() -> Option<PathBuf> {
+    Some(std::env::temp_dir().join(format!("cosmic-weather-{}", std::process::id())))
+}
+
+/// Reads a previously stored value, if there is a readable one.
+pub fn load<T: DeserializeOwned>(name: &str) -> Option<T> {
+    load_from(&cache_dir()?, name)
+}
+
+/// Stores a value, replacing the previous one atomically.
+pub fn store<T: Serialize>(name: &str, value: &T) {
+    if let Some(dir) = cache_dir() {
+        store_in(&dir, name, value);
+    }
+}
+
+fn load_from<T: DeserializeOwned>(dir: &Path, name: &str) -> Option<T> {
+    let contents = std::fs::read(dir.join(name)).ok()?;
+    match serde_json::from_slice(&contents) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            eprintln!("Ignoring unreadable cache file {}: {}", name, err);
+            None
+        }
+    }
+}
+
+fn store_in<T: Serialize>(dir: &Path, name: &str, value: &T) {
+    let result = std::fs::create_dir_all(dir)
+        .and_then(|()| serde_json::to_vec(value).map_err(std::io::Error::from))
+        .and_then(|contents| {
+            let tmp = dir.join(format!("{}.tmp", name));
+            std::fs::write(&tmp, contents)?;
+            std::fs::rename(&tmp, dir.join(name))
+        });
+
+    if let Err(err) = result {
+        eprintln!("Error writing cache file {}: {}", name, err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A directory of its own per test, as tests run in parallel
+    fn test_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cosmic-weather-{}-{}", std::process::id(), test));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn stored_values_are_loaded_back() {
+        let dir = test_dir("round-trip");
+
+        store_in(&dir, "value.json", &vec![1.5, -2.0]);
+        assert_eq!(load_from::<Vec<f64>>(&dir, "value.json"), Some(vec![1.5, -2.0]));
+
+        // Storing again replaces the value without leaving the temporary file
+        store_in(&dir, "value.json", &vec![3.0]);
+        assert_eq!(load_from::<Vec<f64>>(&dir, "value.json"), Some(vec![3.0]));
+        assert!(!dir.join("value.json.tmp").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn missing_and_unreadable_files_are_ignored() {
+        let dir = test_dir("unreadable");
+
+        assert_eq!(load_from::<Vec<f64>>(&dir, "missing.json"), None);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("broken.json"), "{\"truncated").unwrap();
+        assert_eq!(load_from::<Vec<f64>>(&dir, "broken.json"), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
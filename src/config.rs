@@ -1,27 +1,458 @@
 // SPDX-License-Identifier: MIT
 
-use cosmic::cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry};
+use crate::units::{UnitOverrides, UnitSystem, Units};
+use crate::weather::{Provider, ProviderOptions};
+use cosmic::cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, ConfigGet, CosmicConfigEntry};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, Clone, CosmicConfigEntry, Eq, PartialEq)]
-#[version = 2]
+/// A saved place to show the weather for.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Location {
+    pub name: String,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+impl Location {
+    /// The coordinates, once both are set.
+    pub fn coordinates(&self) -> Option<(f64, f64)> {
+        Some((self.latitude?, self.longitude?))
+    }
+}
+
+/// Why a typed coordinate was rejected.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CoordinateError {
+    NotANumber,
+    OutOfRange { min: f64, max: f64 },
+}
+
+/// Parses a latitude typed by the user; empty input clears it.
+pub fn parse_latitude(input: &str) -> Result<Option<f64>, CoordinateError> {
+    parse_coordinate(input, 90.0)
+}
+
+/// Parses a longitude typed by the user; empty input clears it.
+pub fn parse_longitude(input: &str) -> Result<Option<f64>, CoordinateError> {
+    parse_coordinate(input, 180.0)
+}
+
+fn parse_coordinate(input: &str, limit: f64) -> Result<Option<f64>, CoordinateError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+
+    let value = input
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+        .ok_or(CoordinateError::NotANumber)?;
+    if !(-limit..=limit).contains(&value) {
+        return Err(CoordinateError::OutOfRange { min: -limit, max: limit });
+    }
+    Ok(Some(value))
+}
+
+#[derive(Debug, Clone, CosmicConfigEntry, PartialEq)]
+#[version = 3]
 pub struct Config {
-    pub latitude: Option<String>,
-    pub longitude: Option<String>,
-    pub city: Option<String>,
-    pub units: String, // 'metric', 'imperial', 'kelvin'
+    pub locations: Vec<Location>,
+    pub active_location: usize, // index into `locations`
+    pub auto_location: bool, // follow the position reported by GeoClue
+    pub units: Units,
+    pub unit_overrides: UnitOverrides, // units replacing those of `units`, per quantity
     pub auto_update: bool,
     pub update_interval: u64, // in minutes
+    pub adaptive_update: bool, // pick the interval from the forecast and power source
+    pub provider: Provider,
+    pub fallback_providers: Vec<Provider>, // tried in this order when `provider` fails
+    pub met_complete: bool, // use MET Norway's 'complete' endpoint
+    pub api_key: Option<String>, // for providers that need one, e.g. OpenWeatherMap
+    pub geocoding_url: Option<String>, // defaults to Open-Meteo's geocoding API
+    pub reverse_geocoding_url: Option<String>, // defaults to OpenStreetMap's Nominatim
+    pub alerts_url: Option<String>, // defaults to MET Norway's MetAlerts feed
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            locations: Vec::new(),
+            active_location: 0,
+            auto_location: false,
+            units: Units::default(),
+            unit_overrides: UnitOverrides::default(),
+            auto_update: false,
+            update_interval: 0,
+            adaptive_update: false,
+            provider: Provider::default(),
+            fallback_providers: vec![Provider::OpenMeteo], // keyless, so it works everywhere
+            met_complete: false,
+            api_key: None,
+            geocoding_url: None,
+            reverse_geocoding_url: None,
+            alerts_url: None,
+        }
+    }
 }
 
 impl Config {
-    pub fn new(lat: Option<String>, lon: Option<String>, city: Option<String>) -> Self {
+    /// Reads the config of `app_id`, carrying the settings of a version 2
+    /// config over on the first start after upgrading. Returns whether the
+    /// config was migrated and should be saved.
+    pub fn load(app_id: &str) -> (Self, bool) {
+        let Ok(context) = cosmic_config::Config::new(app_id, Self::VERSION) else {
+            return (Self::default(), false);
+        };
+        let config = match Self::get_entry(&context) {
+            Ok(config) => config,
+            Err((_errors, config)) => config,
+        };
+        if context.get::<Units>("units").is_ok() {
+            return (config, false);
+        }
+
+        // Nothing saved in this version yet, look for a previous one
+        let previous = cosmic_config::Config::new(app_id, ConfigV2::VERSION)
+            .ok()
+            .filter(|context| context.get::<String>("units").is_ok())
+            .map(|context| match ConfigV2::get_entry(&context) {
+                Ok(config) => config,
+                Err((_errors, config)) => config,
+            });
+        match previous {
+            Some(previous) => (previous.into(), true),
+            None => (config, false),
+        }
+    }
+
+    pub fn active_location(&self) -> Option<&Location> {
+        self.locations.get(self.active_location)
+    }
+
+    /// The active location, creating an empty one if none is saved yet.
+    pub fn active_location_mut(&mut self) -> &mut Location {
+        if self.locations.is_empty() {
+            self.locations.push(Location::default());
+        }
+        self.active_location = self.active_location.min(self.locations.len() - 1);
+        &mut self.locations[self.active_location]
+    }
+
+    /// Switches to the location at `index`. Returns whether it changed.
+    pub fn select_location(&mut self, index: usize) -> bool {
+        if index >= self.locations.len() || index == self.active_location {
+            return false;
+        }
+        self.active_location = index;
+        true
+    }
+
+    /// Adds an empty location and switches to it.
+    pub fn add_location(&mut self) {
+        self.locations.push(Location::default());
+        self.active_location = self.locations.len() - 1;
+    }
+
+    /// Removes the active location, switching to the one before it. The last
+    /// location is kept. Returns whether one was removed.
+    pub fn remove_active_location(&mut self) -> bool {
+        if self.locations.len() <= 1 {
+            return false;
+        }
+        let index = self.active_location.min(self.locations.len() - 1);
+        self.locations.remove(index);
+        self.active_location = index.saturating_sub(1);
+        true
+    }
+
+    /// Units to display weather data in.
+    pub fn unit_system(&self) -> UnitSystem {
+        self.units.system().with_overrides(&self.unit_overrides)
+    }
+
+    pub fn geocoding_url(&self) -> String {
+        self.geocoding_url
+            .clone()
+            .filter(|url| !url.is_empty())
+            .unwrap_or_else(|| crate::geocoding::DEFAULT_GEOCODING_URL.to_string())
+    }
+
+    pub fn reverse_geocoding_url(&self) -> String {
+        self.reverse_geocoding_url
+            .clone()
+            .filter(|url| !url.is_empty())
+            .unwrap_or_else(|| crate::geocoding::DEFAULT_REVERSE_GEOCODING_URL.to_string())
+    }
+
+    pub fn alerts_url(&self) -> String {
+        self.alerts_url
+            .clone()
+            .filter(|url| !url.is_empty())
+            .unwrap_or_else(|| crate::alerts::DEFAULT_ALERTS_URL.to_string())
+    }
+
+    /// The selected provider followed by the enabled fallbacks, leaving out
+    /// those that need an API key when none is set.
+    pub fn provider_chain(&self) -> Vec<Provider> {
+        let has_api_key = self.api_key.as_ref().is_some_and(|key| !key.trim().is_empty());
+        let mut chain = vec![self.provider];
+        for &provider in &self.fallback_providers {
+            if !chain.contains(&provider) && (has_api_key || !provider.needs_api_key()) {
+                chain.push(provider);
+            }
+        }
+        chain
+    }
+
+    /// Providers that can be used as a fallback, the enabled ones first in
+    /// the order they are tried.
+    pub fn fallback_candidates(&self) -> Vec<(Provider, bool)> {
+        let enabled = self
+            .fallback_providers
+            .iter()
+            .filter(|provider| **provider != self.provider)
+            .map(|provider| (*provider, true));
+        let disabled = Provider::ALL
+            .iter()
+            .filter(|provider| **provider != self.provider && !self.fallback_providers.contains(provider))
+            .map(|provider| (*provider, false));
+        enabled.chain(disabled).collect()
+    }
+
+    /// Adds `provider` at the end of the fallbacks, or removes it.
+    pub fn set_fallback(&mut self, provider: Provider, enabled: bool) {
+        self.fallback_providers.retain(|p| *p != provider);
+        if enabled {
+            self.fallback_providers.push(provider);
+        }
+    }
+
+    /// Moves an enabled fallback one place earlier in the chain.
+    pub fn raise_fallback(&mut self, provider: Provider) {
+        if let Some(index) = self.fallback_providers.iter().position(|p| *p == provider)
+            && index > 0
+        {
+            self.fallback_providers.swap(index, index - 1);
+        }
+    }
+
+    pub fn provider_options(&self) -> ProviderOptions {
+        ProviderOptions {
+            met_complete: self.met_complete,
+            api_key: self.api_key.clone().filter(|key| !key.trim().is_empty()),
+        }
+    }
+}
+
+/// The version 2 config, only read to migrate it.
+#[derive(Debug, Default, Clone, CosmicConfigEntry, Eq, PartialEq)]
+#[version = 2]
+struct ConfigV2 {
+    latitude: Option<String>,
+    longitude: Option<String>,
+    city: Option<String>,
+    units: String, // 'metric', 'imperial', 'kelvin'
+    auto_update: bool,
+    update_interval: u64, // in minutes
+}
+
+impl From<ConfigV2> for Config {
+    fn from(previous: ConfigV2) -> Self {
+        let mut locations = Vec::new();
+        if previous.latitude.is_some() || previous.longitude.is_some() || previous.city.is_some() {
+            // Coordinates that never parsed didn't fetch anything before either
+            locations.push(Location {
+                name: previous.city.unwrap_or_default(),
+                latitude: previous.latitude.as_deref().and_then(|l| parse_latitude(l).ok().flatten()),
+                longitude: previous.longitude.as_deref().and_then(|l| parse_longitude(l).ok().flatten()),
+            });
+        }
+
         Self {
-            latitude: lat,
-            longitude: lon,
-            city,
-            units: "metric".to_string(),
-            auto_update: true,
-            update_interval: 15, // 15 minutes by default
+            locations,
+            units: Units::from_name(&previous.units),
+            auto_update: previous.auto_update,
+            update_interval: previous.update_interval,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coordinates_are_range_checked() {
+        assert_eq!(parse_latitude(" -29.1629 "), Ok(Some(-29.1629)));
+        assert_eq!(parse_latitude(""), Ok(None));
+        assert_eq!(parse_latitude("abc"), Err(CoordinateError::NotANumber));
+        assert_eq!(parse_latitude("NaN"), Err(CoordinateError::NotANumber));
+        assert_eq!(
+            parse_latitude("91"),
+            Err(CoordinateError::OutOfRange { min: -90.0, max: 90.0 })
+        );
+        assert_eq!(parse_longitude("-180"), Ok(Some(-180.0)));
+        assert_eq!(
+            parse_longitude("180.5"),
+            Err(CoordinateError::OutOfRange { min: -180.0, max: 180.0 })
+        );
+    }
+
+    fn location(name: &str) -> Location {
+        Location {
+            name: name.to_string(),
+            ..Default::default()
         }
     }
+
+    #[test]
+    fn locations_can_be_added_and_selected() {
+        let mut config = Config::default();
+
+        config.add_location();
+        config.active_location_mut().name = "Porto Alegre".to_string();
+        config.add_location();
+        assert_eq!(config.locations, vec![location("Porto Alegre"), location("")]);
+        assert_eq!(config.active_location, 1);
+
+        assert!(config.select_location(0));
+        assert_eq!(config.active_location().map(|l| l.name.as_str()), Some("Porto Alegre"));
+        assert!(!config.select_location(0)); // already active
+        assert!(!config.select_location(2)); // out of range
+        assert_eq!(config.active_location, 0);
+    }
+
+    #[test]
+    fn removing_a_location_selects_the_previous_one() {
+        let mut config = Config {
+            locations: vec![location("Oslo"), location("São Paulo"), location("New York")],
+            active_location: 2,
+            ..Default::default()
+        };
+
+        assert!(config.remove_active_location());
+        assert_eq!(config.locations, vec![location("Oslo"), location("São Paulo")]);
+        assert_eq!(config.active_location, 1);
+
+        config.select_location(0);
+        assert!(config.remove_active_location());
+        assert_eq!(config.locations, vec![location("São Paulo")]);
+        assert_eq!(config.active_location, 0);
+
+        // The last location stays
+        assert!(!config.remove_active_location());
+        assert_eq!(config.locations, vec![location("São Paulo")]);
+    }
+
+    #[test]
+    fn fallbacks_follow_the_selected_provider() {
+        let mut config = Config {
+            provider: Provider::OpenMeteo,
+            fallback_providers: vec![Provider::OpenWeatherMap, Provider::OpenMeteo, Provider::MetNorway],
+            ..Default::default()
+        };
+
+        // Without a key OpenWeatherMap is skipped, the selected one isn't repeated
+        assert_eq!(config.provider_chain(), vec![Provider::OpenMeteo, Provider::MetNorway]);
+
+        config.api_key = Some("0123456789abcdef".to_string());
+        assert_eq!(
+            config.provider_chain(),
+            vec![Provider::OpenMeteo, Provider::OpenWeatherMap, Provider::MetNorway]
+        );
+    }
+
+    #[test]
+    fn fallbacks_can_be_toggled_and_reordered() {
+        let mut config = Config {
+            provider: Provider::MetNorway,
+            fallback_providers: vec![Provider::OpenMeteo],
+            ..Default::default()
+        };
+
+        config.set_fallback(Provider::BrightSky, true);
+        config.raise_fallback(Provider::BrightSky);
+        config.raise_fallback(Provider::BrightSky); // already first
+        assert_eq!(config.fallback_providers, vec![Provider::BrightSky, Provider::OpenMeteo]);
+
+        config.set_fallback(Provider::OpenMeteo, false);
+        assert_eq!(
+            config.fallback_candidates(),
+            vec![
+                (Provider::BrightSky, true),
+                (Provider::OpenMeteo, false),
+                (Provider::Nws, false),
+                (Provider::OpenWeatherMap, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn single_location_v2_config_is_migrated() {
+        let previous = ConfigV2 {
+            latitude: Some("-23.5505".to_string()),
+            longitude: Some("-46.6333".to_string()),
+            city: Some("São Paulo".to_string()),
+            units: "imperial".to_string(),
+            auto_update: true,
+            update_interval: 30,
+        };
+
+        let config = Config::from(previous);
+
+        assert_eq!(
+            config.locations,
+            vec![Location {
+                name: "São Paulo".to_string(),
+                latitude: Some(-23.5505),
+                longitude: Some(-46.6333),
+            }]
+        );
+        assert_eq!(config.active_location, 0);
+        assert_eq!(config.units, Units::Imperial);
+        assert_eq!(config.update_interval, 30);
+        assert_eq!(config.fallback_providers, vec![Provider::OpenMeteo]);
+    }
+
+    #[test]
+    fn open_meteo_is_the_default_fallback() {
+        let config = Config {
+            provider: Provider::MetNorway,
+            ..Default::default()
+        };
+
+        assert_eq!(config.provider_chain(), vec![Provider::MetNorway, Provider::OpenMeteo]);
+    }
+
+    #[test]
+    fn invalid_v2_coordinates_are_dropped() {
+        let previous = ConfigV2 {
+            latitude: Some("abc".to_string()),
+            longitude: Some("10.75".to_string()),
+            city: Some("Typo".to_string()),
+            units: "kelvin".to_string(),
+            ..Default::default()
+        };
+
+        let config = Config::from(previous);
+
+        assert_eq!(config.locations[0].name, "Typo");
+        assert_eq!(config.locations[0].latitude, None);
+        assert_eq!(config.locations[0].longitude, Some(10.75));
+        assert_eq!(config.active_location, 0);
+        assert_eq!(config.units, Units::Kelvin);
+    }
+
+    #[test]
+    fn empty_v2_config_has_no_locations() {
+        let config = Config::from(ConfigV2 {
+            units: "metric".to_string(),
+            ..Default::default()
+        });
+
+        assert!(config.locations.is_empty());
+        assert_eq!(config.units, Units::Metric);
+    }
 }
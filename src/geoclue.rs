@@ -0,0 +1,291 @@
+// SPDX-License-Identifier: MIT
+
+//! Current position from GeoClue2 over D-Bus.
+
+use futures_util::{Stream, StreamExt};
+use zbus::zvariant::{ObjectPath, OwnedObjectPath};
+
+/// Desktop id GeoClue checks its permissions against.
+const DESKTOP_ID: &str = "com.github.marcos.CosmicWeather";
+
+/// GeoClue's `GCLUE_ACCURACY_LEVEL_CITY`; weather doesn't need street precision.
+const ACCURACY_LEVEL_CITY: u32 = 4;
+
+/// Moves shorter than this don't change the weather enough to fetch again.
+pub const REFRESH_DISTANCE_KM: f64 = 5.0;
+
+#[zbus::proxy(
+    interface = "org.freedesktop.GeoClue2.Manager",
+    default_service = "org.freedesktop.GeoClue2",
+    default_path = "/org/freedesktop/GeoClue2/Manager"
+)]
+trait Manager {
+    fn get_client(&self) -> zbus::Result<OwnedObjectPath>;
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.GeoClue2.Client",
+    default_service = "org.freedesktop.GeoClue2"
+)]
+trait Client {
+    fn start(&self) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn set_desktop_id(&self, id: &str) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn set_distance_threshold(&self, meters: u32) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn set_requested_accuracy_level(&self, level: u32) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn location_updated(&self, old: ObjectPath<'_>, new: ObjectPath<'_>) -> zbus::Result<()>;
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.GeoClue2.Location",
+    default_service = "org.freedesktop.GeoClue2"
+)]
+trait Location {
+    #[zbus(property)]
+    fn latitude(&self) -> zbus::Result<f64>;
+
+    #[zbus(property)]
+    fn longitude(&self) -> zbus::Result<f64>;
+
+    #[zbus(property)]
+    fn accuracy(&self) -> zbus::Result<f64>;
+}
+
+/// A position reported by GeoClue.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Radius of the uncertainty in meters.
+    pub accuracy: f64,
+}
+
+impl Position {
+    /// Great-circle distance to `other` in kilometers.
+    pub fn distance_km(&self, other: &Position) -> f64 {
+        const EARTH_RADIUS_KM: f64 = 6371.0;
+
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let dlat = lat2 - lat1;
+        let dlon = (other.longitude - self.longitude).to_radians();
+        let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+    }
+
+    /// Whether moving from `self` to `other` warrants fetching the weather again.
+    pub fn moved_significantly(&self, other: &Position) -> bool {
+        self.distance_km(other) >= REFRESH_DISTANCE_KM
+    }
+}
+
+/// Starts a GeoClue client on `connection` and streams the positions it reports.
+///
+/// GeoClue itself only reports moves beyond `REFRESH_DISTANCE_KM`.
+pub async fn positions(
+    connection: &zbus::Connection,
+) -> zbus::Result<impl Stream<Item = Position> + use<>> {
+    let manager = ManagerProxy::new(connection).await?;
+    let path = manager.get_client().await?;
+    let client = ClientProxy::builder(connection).path(path)?.build().await?;
+
+    client.set_desktop_id(DESKTOP_ID).await?;
+    client
+        .set_distance_threshold((REFRESH_DISTANCE_KM * 1000.0) as u32)
+        .await?;
+    client
+        .set_requested_accuracy_level(ACCURACY_LEVEL_CITY)
+        .await?;
+
+    // Subscribe before starting so the first fix isn't missed
+    let updates = client.receive_location_updated().await?;
+    client.start().await?;
+
+    let connection = connection.clone();
+    Ok(updates.filter_map(move |signal| {
+        let connection = connection.clone();
+        async move {
+            let path = signal.args().ok()?.new.into();
+            match position(&connection, path).await {
+                Ok(position) => Some(position),
+                Err(err) => {
+                    eprintln!("Error reading GeoClue location: {}", err);
+                    None
+                }
+            }
+        }
+    }))
+}
+
+async fn position(connection: &zbus::Connection, path: OwnedObjectPath) -> zbus::Result<Position> {
+    let location = LocationProxy::builder(connection).path(path)?.build().await?;
+    Ok(Position {
+        latitude: location.latitude().await?,
+        longitude: location.longitude().await?,
+        accuracy: location.accuracy().await?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zbus::object_server::SignalEmitter;
+
+    const CLIENT_PATH: &str = "/org/freedesktop/GeoClue2/Client/1";
+    const LOCATION_PATH: &str = "/org/freedesktop/GeoClue2/Location/1";
+
+    struct MockManager;
+
+    #[zbus::interface(name = "org.freedesktop.GeoClue2.Manager")]
+    impl MockManager {
+        fn get_client(&self) -> OwnedObjectPath {
+            ObjectPath::from_static_str_unchecked(CLIENT_PATH).into()
+        }
+    }
+
+    #[derive(Default)]
+    struct MockClient {
+        desktop_id: String,
+        distance_threshold: u32,
+        requested_accuracy_level: u32,
+    }
+
+    #[zbus::interface(name = "org.freedesktop.GeoClue2.Client")]
+    impl MockClient {
+        async fn start(
+            &self,
+            #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+        ) -> zbus::fdo::Result<()> {
+            // GeoClue refuses clients that don't identify themselves
+            if self.desktop_id.is_empty() {
+                return Err(zbus::fdo::Error::AccessDenied("no desktop id".into()));
+            }
+            assert_eq!(self.distance_threshold, 5000);
+            assert_eq!(self.requested_accuracy_level, ACCURACY_LEVEL_CITY);
+
+            Self::location_updated(
+                &emitter,
+                ObjectPath::from_static_str_unchecked("/"),
+                ObjectPath::from_static_str_unchecked(LOCATION_PATH),
+            )
+            .await?;
+            Ok(())
+        }
+
+        #[zbus(property)]
+        fn desktop_id(&self) -> &str {
+            &self.desktop_id
+        }
+
+        #[zbus(property)]
+        fn set_desktop_id(&mut self, id: String) {
+            self.desktop_id = id;
+        }
+
+        #[zbus(property)]
+        fn distance_threshold(&self) -> u32 {
+            self.distance_threshold
+        }
+
+        #[zbus(property)]
+        fn set_distance_threshold(&mut self, meters: u32) {
+            self.distance_threshold = meters;
+        }
+
+        #[zbus(property)]
+        fn requested_accuracy_level(&self) -> u32 {
+            self.requested_accuracy_level
+        }
+
+        #[zbus(property)]
+        fn set_requested_accuracy_level(&mut self, level: u32) {
+            self.requested_accuracy_level = level;
+        }
+
+        #[zbus(signal)]
+        async fn location_updated(
+            emitter: &SignalEmitter<'_>,
+            old: ObjectPath<'_>,
+            new: ObjectPath<'_>,
+        ) -> zbus::Result<()>;
+    }
+
+    struct MockLocation;
+
+    #[zbus::interface(name = "org.freedesktop.GeoClue2.Location")]
+    impl MockLocation {
+        #[zbus(property)]
+        fn latitude(&self) -> f64 {
+            59.9139
+        }
+
+        #[zbus(property)]
+        fn longitude(&self) -> f64 {
+            10.7522
+        }
+
+        #[zbus(property)]
+        fn accuracy(&self) -> f64 {
+            2500.0
+        }
+    }
+
+    /// A private peer-to-peer bus with the mock GeoClue service on the other end.
+    async fn mock_geoclue() -> (zbus::Connection, zbus::Connection) {
+        let (service, client) = tokio::net::UnixStream::pair().unwrap();
+        let guid = zbus::Guid::generate();
+
+        let service = zbus::connection::Builder::unix_stream(service)
+            .server(guid)
+            .unwrap()
+            .p2p()
+            .serve_at("/org/freedesktop/GeoClue2/Manager", MockManager)
+            .unwrap()
+            .serve_at(CLIENT_PATH, MockClient::default())
+            .unwrap()
+            .serve_at(LOCATION_PATH, MockLocation)
+            .unwrap()
+            .build();
+        let client = zbus::connection::Builder::unix_stream(client).p2p().build();
+
+        tokio::try_join!(service, client).unwrap()
+    }
+
+    #[tokio::test]
+    async fn streams_position_from_geoclue() {
+        let (_service, connection) = mock_geoclue().await;
+
+        let mut positions = Box::pin(positions(&connection).await.unwrap());
+        let position = tokio::time::timeout(std::time::Duration::from_secs(5), positions.next())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            position,
+            Position {
+                latitude: 59.9139,
+                longitude: 10.7522,
+                accuracy: 2500.0
+            }
+        );
+    }
+
+    #[test]
+    fn distance_between_cities() {
+        let oslo = Position { latitude: 59.9139, longitude: 10.7522, accuracy: 0.0 };
+        let bergen = Position { latitude: 60.3913, longitude: 5.3221, accuracy: 0.0 };
+
+        assert!((oslo.distance_km(&bergen) - 305.0).abs() < 5.0);
+        assert!(oslo.moved_significantly(&bergen));
+
+        let nearby = Position { latitude: 59.92, longitude: 10.76, accuracy: 0.0 };
+        assert!(!oslo.moved_significantly(&nearby));
+    }
+}
@@ -0,0 +1,247 @@
+// SPDX-License-Identifier: MIT
+
+//! Place name search, so locations can be picked by name instead of coordinates,
+//! and reverse lookups to label coordinates with a place name.
+//!
+//! Search talks to an Open-Meteo geocoding shaped API, reverse lookups to a
+//! Nominatim shaped one. Both base URLs are configurable so a self-hosted or
+//! local stand-in can serve them.
+
+use crate::cache;
+use crate::weather::{WeatherError, HTTP_CLIENT};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Used when `Config::geocoding_url` is not set.
+pub const DEFAULT_GEOCODING_URL: &str = "https://geocoding-api.open-meteo.com";
+
+/// Used when `Config::reverse_geocoding_url` is not set.
+pub const DEFAULT_REVERSE_GEOCODING_URL: &str = "https://nominatim.openstreetmap.org";
+
+/// Cache file mapping coordinates to resolved place names.
+const PLACE_CACHE_FILE: &str = "places.json";
+
+/// Maximum number of places returned by a search.
+const MAX_RESULTS: u8 = 8;
+
+/// A place matching a search.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Place {
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub admin1: Option<String>, // state or region
+    pub country: Option<String>,
+}
+
+impl Place {
+    /// Name with region and country, e.g. "Caxias do Sul, Rio Grande do Sul, Brazil".
+    pub fn display_name(&self) -> String {
+        [Some(&self.name), self.admin1.as_ref(), self.country.as_ref()]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    // Omitted by the API when nothing matches
+    #[serde(default)]
+    results: Vec<Place>,
+}
+
+/// Searches places by name, with names localized to `language` where available.
+pub async fn search(base_url: String, query: String, language: String) -> Result<Vec<Place>, WeatherError> {
+    let url = format!("{}/v1/search", base_url.trim_end_matches('/'));
+    let count = MAX_RESULTS.to_string();
+
+    let response = HTTP_CLIENT
+        .get(&url)
+        .query(&[
+            ("name", query.as_str()),
+            ("count", count.as_str()),
+            ("language", language.as_str()),
+            ("format", "json"),
+        ])
+        .send()
+        .await?;
+
+    if response.status().is_success() {
+        parse_search(&response.text().await?)
+    } else {
+        Err(WeatherError::from_status(response.status(), response.headers()))
+    }
+}
+
+fn parse_search(json: &str) -> Result<Vec<Place>, WeatherError> {
+    let search_response: SearchResponse = serde_json::from_str(json)?;
+    Ok(search_response.results)
+}
+
+#[derive(Debug, Deserialize)]
+struct ReverseResponse {
+    name: Option<String>,
+    #[serde(default)]
+    address: Address,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Address {
+    city: Option<String>,
+    town: Option<String>,
+    village: Option<String>,
+    municipality: Option<String>,
+    state: Option<String>,
+    country: Option<String>,
+}
+
+impl ReverseResponse {
+    // Locality with its region, e.g. "Caxias do Sul, Rio Grande do Sul"
+    fn label(self) -> Option<String> {
+        let address = self.address;
+        let locality = address
+            .city
+            .or(address.town)
+            .or(address.village)
+            .or(address.municipality)
+            .or(self.name)
+            .filter(|name| !name.is_empty())?;
+
+        Some(match address.state.or(address.country) {
+            Some(region) => format!("{}, {}", locality, region),
+            None => locality,
+        })
+    }
+}
+
+/// Resolves coordinates to a human-readable place name.
+///
+/// Names are cached on disk, so each location is only looked up once.
+pub async fn reverse(base_url: String, lat: f64, lon: f64, language: String) -> Result<String, WeatherError> {
+    let key = place_key(lat, lon, &language);
+    if let Some(name) = cached_name(&key) {
+        return Ok(name);
+    }
+
+    let url = format!("{}/reverse", base_url.trim_end_matches('/'));
+    let lat = format!("{:.4}", lat);
+    let lon = format!("{:.4}", lon);
+
+    let response = HTTP_CLIENT
+        .get(&url)
+        .query(&[
+            ("lat", lat.as_str()),
+            ("lon", lon.as_str()),
+            ("format", "jsonv2"),
+            ("zoom", "10"), // city level
+            ("accept-language", language.as_str()),
+        ])
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(WeatherError::from_status(response.status(), response.headers()));
+    }
+
+    let reverse_response: ReverseResponse = response.json().await?;
+    let name = reverse_response.label().ok_or(WeatherError::NoData)?;
+    remember_name(key, &name);
+
+    Ok(name)
+}
+
+// Coordinates are rounded like in the requests, names differ per language
+fn place_key(lat: f64, lon: f64, language: &str) -> String {
+    format!("{:.4},{:.4},{}", lat, lon, language)
+}
+
+fn cached_name(key: &str) -> Option<String> {
+    let mut places: HashMap<String, String> = cache::load(PLACE_CACHE_FILE)?;
+    places.remove(key)
+}
+
+fn remember_name(key: String, name: &str) {
+    // Reload right before writing to keep entries added in the meantime
+    let mut places: HashMap<String, String> = cache::load(PLACE_CACHE_FILE).unwrap_or_default();
+    places.insert(key, name.to_string());
+    cache::store(PLACE_CACHE_FILE, &places);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEARCH: &str = include_str!("../tests/fixtures/open_meteo_search.json");
+    const REVERSE: &str = include_str!("../tests/fixtures/nominatim_reverse.json");
+
+    fn reverse_response(json: &str) -> ReverseResponse {
+        serde_json::from_str(json).expect("response should deserialize")
+    }
+
+    #[test]
+    fn search_results_are_parsed() {
+        let places = parse_search(SEARCH).unwrap();
+
+        assert_eq!(places.len(), 2);
+        assert_eq!(
+            places[0],
+            Place {
+                name: "Caxias do Sul".to_string(),
+                latitude: -29.16806,
+                longitude: -51.17944,
+                admin1: Some("Rio Grande do Sul".to_string()),
+                country: Some("Brasil".to_string()),
+            }
+        );
+        assert_eq!(places[0].display_name(), "Caxias do Sul, Rio Grande do Sul, Brasil");
+        // Places without a region leave it out of the name
+        assert_eq!(places[1].display_name(), "Caxias, Brasil");
+    }
+
+    #[test]
+    fn searches_without_matches_are_empty() {
+        assert_eq!(parse_search(r#"{"generationtime_ms": 0.41}"#), Ok(Vec::new()));
+        assert!(matches!(parse_search("<html>"), Err(WeatherError::Parse(_))));
+    }
+
+    #[test]
+    fn reverse_lookup_is_labeled_with_locality_and_state() {
+        assert_eq!(
+            reverse_response(REVERSE).label().as_deref(),
+            Some("Caxias do Sul, Rio Grande do Sul")
+        );
+    }
+
+    #[test]
+    fn label_falls_back_to_smaller_places_and_the_country() {
+        let village = r#"{"name": "Fazenda", "address": {"village": "Vila Cristina", "country": "Brasil"}}"#;
+        assert_eq!(reverse_response(village).label().as_deref(), Some("Vila Cristina, Brasil"));
+
+        let named = r#"{"name": "Ilha da Queimada Grande"}"#;
+        assert_eq!(reverse_response(named).label().as_deref(), Some("Ilha da Queimada Grande"));
+
+        // Open sea, nothing to call the place
+        let unnamed = r#"{"name": "", "address": {"country": "Brasil"}}"#;
+        assert_eq!(reverse_response(unnamed).label(), None);
+    }
+
+    #[test]
+    fn place_names_are_cached_per_language() {
+        let key = place_key(-29.16291, -51.18334, "pt");
+        assert_eq!(key, "-29.1629,-51.1833,pt");
+
+        remember_name(key.clone(), "Caxias do Sul, Rio Grande do Sul");
+        remember_name(place_key(59.91, 10.75, "pt"), "Oslo, Oslo");
+
+        assert_eq!(cached_name(&key).as_deref(), Some("Caxias do Sul, Rio Grande do Sul"));
+        assert_eq!(
+            cached_name(&place_key(-29.16289, -51.18331, "pt")).as_deref(),
+            Some("Caxias do Sul, Rio Grande do Sul")
+        );
+        assert_eq!(cached_name(&place_key(-29.1629, -51.1833, "en")), None);
+        assert_eq!(cached_name(&place_key(59.91, 10.75, "pt")).as_deref(), Some("Oslo, Oslo"));
+    }
+}
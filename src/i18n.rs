@@ -38,6 +38,13 @@ pub static LANGUAGE_LOADER: LazyLock<FluentLanguageLoader> = LazyLock::new(|| {
     loader
 });
 
+/// The primary language currently in use, as an ISO 639 code such as "pt".
+pub fn language_code() -> String {
+    LANGUAGE_LOADER
+        .current_languages()
+        .first()
+        .map_or_else(|| "en".to_string(), |lang| lang.language.to_string())
+}
 
 /// Request a localized string by ID from the i18n/ directory.
 #[macro_export]
@@ -1,8 +1,17 @@
 // SPDX-License-Identifier: MPL-2.0
 
+mod alerts;
 mod app;
+mod cache;
 mod config;
+mod geoclue;
+mod geocoding;
 mod i18n;
+mod meteo;
+mod schedule;
+mod symbol;
+mod units;
+mod upower;
 mod weather;
 
 fn main() -> cosmic::iced::Result {
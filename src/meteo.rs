@@ -0,0 +1,308 @@
+// SPDX-License-Identifier: MIT
+
+//! Meteorological formulas for quantities the providers don't report.
+//!
+//! Inputs are in °C, percent relative humidity, m/s and hPa, as in `WeatherData`.
+
+use serde::{Deserialize, Serialize};
+
+/// Wind chill only applies at or below this temperature, in °C.
+const WIND_CHILL_MAX_TEMPERATURE: f64 = 10.0;
+
+/// ...and above this wind speed, in km/h.
+const WIND_CHILL_MIN_WIND_SPEED: f64 = 4.8;
+
+/// The heat index only applies from this temperature, in °C.
+const HEAT_INDEX_MIN_TEMPERATURE: f64 = 27.0;
+
+/// How warm or cold it feels: wind chill in the cold, heat index in the heat
+/// and Steadman's apparent temperature in between.
+pub fn feels_like(temperature: f64, humidity: f64, wind_speed: f64) -> f64 {
+    let wind_kmh = wind_speed * 3.6;
+    if temperature <= WIND_CHILL_MAX_TEMPERATURE && wind_kmh > WIND_CHILL_MIN_WIND_SPEED {
+        wind_chill(temperature, wind_speed)
+    } else if temperature >= HEAT_INDEX_MIN_TEMPERATURE {
+        heat_index(temperature, humidity)
+    } else {
+        apparent_temperature(temperature, humidity, wind_speed)
+    }
+}
+
+/// Wind chill index as used by Environment Canada and the US National Weather
+/// Service (JAG/TI 2001).
+pub fn wind_chill(temperature: f64, wind_speed: f64) -> f64 {
+    let v = (wind_speed * 3.6).powf(0.16);
+    13.12 + 0.6215 * temperature - 11.37 * v + 0.3965 * temperature * v
+}
+
+/// Heat index after the US National Weather Service: Rothfusz' regression of
+/// Steadman's table, with its adjustments for very dry and very humid air.
+pub fn heat_index(temperature: f64, humidity: f64) -> f64 {
+    let t = temperature * 9.0 / 5.0 + 32.0;
+    let rh = humidity;
+
+    // The simple formula is good enough below 80 °F
+    let simple = 0.5 * (t + 61.0 + (t - 68.0) * 1.2 + rh * 0.094);
+    let index = if (simple + t) / 2.0 < 80.0 {
+        simple
+    } else {
+        let mut index = -42.379 + 2.04901523 * t + 10.14333127 * rh
+            - 0.22475541 * t * rh
+            - 0.00683783 * t * t
+            - 0.05481717 * rh * rh
+            + 0.00122874 * t * t * rh
+            + 0.00085282 * t * rh * rh
+            - 0.00000199 * t * t * rh * rh;
+        if rh < 13.0 && (80.0..=112.0).contains(&t) {
+            index -= (13.0 - rh) / 4.0 * ((17.0 - (t - 95.0).abs()) / 17.0).sqrt();
+        } else if rh > 85.0 && (80.0..=87.0).contains(&t) {
+            index += (rh - 85.0) / 10.0 * ((87.0 - t) / 5.0);
+        }
+        index
+    };
+
+    (index - 32.0) * 5.0 / 9.0
+}
+
+/// Steadman's apparent temperature for shade, in the form used by the
+/// Australian Bureau of Meteorology.
+pub fn apparent_temperature(temperature: f64, humidity: f64, wind_speed: f64) -> f64 {
+    temperature + 0.33 * vapour_pressure(temperature, humidity) - 0.70 * wind_speed - 4.00
+}
+
+/// Water vapour pressure in hPa.
+fn vapour_pressure(temperature: f64, humidity: f64) -> f64 {
+    humidity / 100.0 * 6.105 * (17.27 * temperature / (237.7 + temperature)).exp()
+}
+
+/// Magnus formula coefficients after Alduchov and Eskridge (1996).
+const MAGNUS_A: f64 = 17.625;
+const MAGNUS_B: f64 = 243.04;
+
+/// Dew point in °C from the Magnus formula.
+pub fn dew_point(temperature: f64, humidity: f64) -> f64 {
+    let gamma = (humidity / 100.0).ln() + MAGNUS_A * temperature / (MAGNUS_B + temperature);
+    MAGNUS_B * gamma / (MAGNUS_A - gamma)
+}
+
+/// Water vapour content of the air in g/m³.
+pub fn absolute_humidity(temperature: f64, humidity: f64) -> f64 {
+    let saturation = 6.112 * (MAGNUS_A * temperature / (MAGNUS_B + temperature)).exp();
+    // e in hPa over the specific gas constant of water vapour, in g/m³
+    saturation * humidity * 2.1674 / (273.15 + temperature)
+}
+
+/// Height of cumulus cloud bases above ground in meters, from the spread
+/// between temperature and dew point: about 125 m per degree.
+pub fn cloud_base(temperature: f64, dew_point: f64) -> f64 {
+    (temperature - dew_point).max(0.0) * 125.0
+}
+
+/// Upper bounds in m/s of Beaufort forces 0 to 11, per the WMO table.
+const BEAUFORT_LIMITS: [f64; 12] = [0.5, 1.5, 3.3, 5.5, 7.9, 10.7, 13.8, 17.1, 20.7, 24.4, 28.4, 32.6];
+
+/// Beaufort force of a wind speed in m/s.
+pub fn beaufort(wind_speed: f64) -> u8 {
+    // The table is given to one decimal, so 0.54 m/s is still force 0
+    let speed = (wind_speed * 10.0).round() / 10.0;
+    BEAUFORT_LIMITS
+        .iter()
+        .position(|&limit| speed <= limit)
+        .unwrap_or(BEAUFORT_LIMITS.len()) as u8
+}
+
+/// Point of the 16-point compass rose a wind comes from, 0 being north, 1
+/// north-northeast and so on clockwise.
+pub fn compass_point(degrees: f64) -> u8 {
+    ((degrees.rem_euclid(360.0) / 22.5).round() as u8) % 16
+}
+
+/// Expected change of the air pressure over the next three hours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum PressureTrend {
+    Rising,
+    Steady,
+    Falling,
+}
+
+/// Change in hPa over three hours below which the pressure counts as steady.
+const STEADY_PRESSURE_CHANGE: f64 = 1.0;
+
+/// Trend from the pressure `now` to the forecast pressure `later`, three hours on.
+pub fn pressure_trend(now: f64, later: f64) -> PressureTrend {
+    let change = later - now;
+    if change >= STEADY_PRESSURE_CHANGE {
+        PressureTrend::Rising
+    } else if change <= -STEADY_PRESSURE_CHANGE {
+        PressureTrend::Falling
+    } else {
+        PressureTrend::Steady
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn fahrenheit(celsius: f64) -> f64 {
+        celsius * 9.0 / 5.0 + 32.0
+    }
+
+    fn celsius(fahrenheit: f64) -> f64 {
+        (fahrenheit - 32.0) * 5.0 / 9.0
+    }
+
+    fn assert_near(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{actual} is not within {tolerance} of {expected}"
+        );
+    }
+
+    #[test]
+    fn wind_chill_matches_environment_canada_table() {
+        // Air temperature °C, wind speed km/h, wind chill as tabulated
+        let table = [
+            (5.0, 10.0, 3.0),
+            (0.0, 10.0, -3.0),
+            (0.0, 30.0, -6.0),
+            (-10.0, 20.0, -18.0),
+            (-20.0, 30.0, -33.0),
+            (-30.0, 50.0, -49.0),
+            (-40.0, 60.0, -64.0),
+        ];
+        for (temperature, wind_kmh, expected) in table {
+            assert_near(wind_chill(temperature, wind_kmh / 3.6), expected, 0.5);
+        }
+    }
+
+    #[test]
+    fn heat_index_matches_nws_table() {
+        // Air temperature °F, relative humidity %, heat index °F as tabulated
+        let table = [
+            (80.0, 40.0, 80.0),
+            (86.0, 85.0, 102.0),
+            (90.0, 50.0, 95.0),
+            (94.0, 60.0, 110.0),
+            (100.0, 40.0, 109.0),
+            (104.0, 55.0, 137.0),
+        ];
+        for (temperature, humidity, expected) in table {
+            let index = heat_index(celsius(temperature), humidity);
+            assert_near(fahrenheit(index), expected, 1.0);
+        }
+    }
+
+    #[test]
+    fn apparent_temperature_matches_bom_formula() {
+        // Worked values of the Bureau of Meteorology's formula
+        assert_near(apparent_temperature(20.0, 50.0, 0.0), 19.8, 0.1);
+        assert_near(apparent_temperature(20.0, 50.0, 5.0), 16.3, 0.1);
+        assert_near(apparent_temperature(15.0, 80.0, 2.0), 14.1, 0.1);
+    }
+
+    #[test]
+    fn picks_formula_by_conditions() {
+        // Cold and windy
+        assert_eq!(feels_like(-5.0, 80.0, 8.0), wind_chill(-5.0, 8.0));
+        // Cold but calm
+        assert_eq!(feels_like(5.0, 80.0, 1.0), apparent_temperature(5.0, 80.0, 1.0));
+        // Hot
+        assert_eq!(feels_like(32.0, 60.0, 3.0), heat_index(32.0, 60.0));
+        // Mild
+        assert_eq!(feels_like(18.0, 60.0, 3.0), apparent_temperature(18.0, 60.0, 3.0));
+    }
+
+    #[test]
+    fn derived_quantities_match_reference_values() {
+        assert_near(dew_point(20.0, 50.0), 9.3, 0.1);
+        assert_near(dew_point(30.0, 70.0), 23.9, 0.1);
+        assert_near(absolute_humidity(20.0, 100.0), 17.3, 0.1);
+        assert_near(absolute_humidity(0.0, 100.0), 4.85, 0.05);
+        assert_eq!(cloud_base(20.0, 12.0), 1000.0);
+    }
+
+    #[test]
+    fn beaufort_scale_bounds() {
+        assert_eq!(beaufort(0.0), 0);
+        assert_eq!(beaufort(0.5), 0);
+        assert_eq!(beaufort(0.6), 1);
+        assert_eq!(beaufort(5.5), 3);
+        assert_eq!(beaufort(5.6), 4);
+        assert_eq!(beaufort(32.6), 11);
+        assert_eq!(beaufort(32.7), 12);
+    }
+
+    #[test]
+    fn compass_points_around_the_rose() {
+        assert_eq!(compass_point(0.0), 0);
+        assert_eq!(compass_point(11.0), 0);
+        assert_eq!(compass_point(12.0), 1);
+        assert_eq!(compass_point(90.0), 4);
+        assert_eq!(compass_point(225.0), 10);
+        assert_eq!(compass_point(340.0), 15);
+        assert_eq!(compass_point(355.0), 0);
+    }
+
+    proptest! {
+        #[test]
+        fn wind_chill_is_colder_than_the_air(temperature in -50.0..=10.0f64, wind_speed in 1.34..40.0f64) {
+            prop_assert!(wind_chill(temperature, wind_speed) < temperature);
+        }
+
+        #[test]
+        fn dew_point_never_exceeds_temperature(temperature in -40.0..50.0f64, humidity in 1.0..=100.0f64) {
+            let dew_point = dew_point(temperature, humidity);
+            prop_assert!(dew_point <= temperature + 1e-9);
+            prop_assert!(cloud_base(temperature, dew_point) >= 0.0);
+        }
+
+        #[test]
+        fn saturated_air_has_dew_point_at_temperature(temperature in -40.0..50.0f64) {
+            prop_assert!((dew_point(temperature, 100.0) - temperature).abs() < 1e-9);
+            prop_assert!(cloud_base(temperature, temperature) == 0.0);
+        }
+
+        #[test]
+        fn moister_air_has_higher_dew_point_and_water_content(
+            temperature in -40.0..50.0f64,
+            humidity in 1.0..99.0f64,
+            more in 0.5..50.0f64,
+        ) {
+            let wetter = (humidity + more).min(100.0);
+            prop_assert!(dew_point(temperature, wetter) > dew_point(temperature, humidity));
+            prop_assert!(absolute_humidity(temperature, wetter) > absolute_humidity(temperature, humidity));
+            prop_assert!(absolute_humidity(temperature, humidity) > 0.0);
+        }
+
+        #[test]
+        fn beaufort_grows_with_wind_speed(wind_speed in 0.0..80.0f64, more in 0.0..20.0f64) {
+            let force = beaufort(wind_speed);
+            prop_assert!(force <= 12);
+            prop_assert!(beaufort(wind_speed + more) >= force);
+        }
+
+        #[test]
+        fn compass_point_wraps_around(degrees in -720.0..720.0f64, turns in -3i32..3) {
+            let point = compass_point(degrees);
+            prop_assert!(point < 16);
+            prop_assert_eq!(compass_point(degrees + 360.0 * f64::from(turns)), point);
+        }
+
+        #[test]
+        fn compass_point_of_each_direction(point in 0u8..16, offset in -11.0..11.0f64) {
+            prop_assert_eq!(compass_point(f64::from(point) * 22.5 + offset), point);
+        }
+
+        #[test]
+        fn pressure_trend_is_antisymmetric(before in 950.0..1050.0f64, after in 950.0..1050.0f64) {
+            let expected = match pressure_trend(before, after) {
+                PressureTrend::Rising => PressureTrend::Falling,
+                PressureTrend::Steady => PressureTrend::Steady,
+                PressureTrend::Falling => PressureTrend::Rising,
+            };
+            prop_assert_eq!(pressure_trend(after, before), expected);
+        }
+    }
+}
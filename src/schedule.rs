@@ -0,0 +1,133 @@
+// SPDX-License-Identifier: MIT
+
+//! Refresh intervals of the adaptive update mode.
+
+use crate::weather::Forecast;
+use chrono::{DateTime, Local, Timelike};
+use std::time::Duration;
+
+/// While rain or snow is about to start. MET Norway asks not to poll a location
+/// more often than its data changes, which is rarely faster than this.
+pub const IMMINENT_PRECIPITATION_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Daytime without precipitation in sight.
+pub const DAY_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+/// Overnight, when hardly anyone looks.
+pub const NIGHT_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Upper bound once the battery slowdown is applied.
+pub const MAX_INTERVAL: Duration = Duration::from_secs(2 * 60 * 60);
+
+/// How far ahead precipitation counts as imminent.
+const IMMINENT_HOURS: i64 = 2;
+
+/// Hourly amount in mm, or chance in percent, from which precipitation counts.
+const PRECIPITATION_THRESHOLD: f64 = 0.1;
+const PROBABILITY_THRESHOLD: u8 = 50;
+
+/// Local hours from which, and until which, it's night.
+const NIGHT_START: u32 = 22;
+const NIGHT_END: u32 = 6;
+
+/// Pause until the next refresh, given the forecast shown at `now`.
+pub fn adaptive_interval(forecast: Option<&Forecast>, now: DateTime<Local>, on_battery: bool) -> Duration {
+    let interval = if forecast.is_some_and(|f| precipitation_imminent(f, now)) {
+        IMMINENT_PRECIPITATION_INTERVAL
+    } else if now.hour() >= NIGHT_START || now.hour() < NIGHT_END {
+        NIGHT_INTERVAL
+    } else {
+        DAY_INTERVAL
+    };
+
+    if on_battery {
+        (interval * 2).min(MAX_INTERVAL)
+    } else {
+        interval
+    }
+}
+
+fn precipitation_imminent(forecast: &Forecast, now: DateTime<Local>) -> bool {
+    let end = now + chrono::Duration::hours(IMMINENT_HOURS);
+    forecast
+        .hourly
+        .iter()
+        .filter(|h| h.time + chrono::Duration::hours(1) > now && h.time < end)
+        .any(|h| {
+            h.precipitation >= PRECIPITATION_THRESHOLD
+                || h.precipitation_probability.is_some_and(|p| p >= PROBABILITY_THRESHOLD)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weather::{HourlyForecast, WeatherData};
+    use chrono::TimeZone;
+
+    fn forecast(now: DateTime<Local>, precipitation: &[(i64, f64)]) -> Forecast {
+        let hour = |offset: i64, precipitation: f64| HourlyForecast {
+            time: now + chrono::Duration::hours(offset),
+            temperature: 10.0,
+            description: String::new(),
+            icon: "04d".to_string(),
+            precipitation,
+            precipitation_probability: None,
+            wind_speed: 3.0,
+        };
+
+        Forecast {
+            current: WeatherData {
+                temperature: 10.0,
+                feels_like: 10.0,
+                humidity: Some(80),
+                description: String::new(),
+                icon: "04d".to_string(),
+                uv_index: None,
+                dew_point: None,
+                wind_speed: None,
+                wind_direction: None,
+                wind_gust: None,
+                pressure: None,
+                pressure_trend: None,
+                cloud_layers: None,
+                location: String::new(),
+                timestamp: std::time::SystemTime::now(),
+            },
+            hourly: precipitation.iter().map(|&(offset, mm)| hour(offset, mm)).collect(),
+            daily: Vec::new(),
+            expires: None,
+            alerts: Vec::new(),
+        }
+    }
+
+    fn at(hour: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 3, 12, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn dry_day_and_night() {
+        let noon = at(12);
+        let dry = forecast(noon, &[(0, 0.0), (1, 0.0), (2, 0.0)]);
+        assert_eq!(adaptive_interval(Some(&dry), noon, false), DAY_INTERVAL);
+        assert_eq!(adaptive_interval(None, at(23), false), NIGHT_INTERVAL);
+        assert_eq!(adaptive_interval(None, at(5), false), NIGHT_INTERVAL);
+    }
+
+    #[test]
+    fn precipitation_within_two_hours_is_imminent() {
+        let noon = at(12);
+        let soon = forecast(noon, &[(0, 0.0), (1, 0.4)]);
+        assert_eq!(adaptive_interval(Some(&soon), noon, false), IMMINENT_PRECIPITATION_INTERVAL);
+
+        // Precipitation later on doesn't count yet
+        let later = forecast(noon, &[(0, 0.0), (1, 0.0), (3, 2.0)]);
+        assert_eq!(adaptive_interval(Some(&later), noon, false), DAY_INTERVAL);
+    }
+
+    #[test]
+    fn battery_slows_down_within_bounds() {
+        assert_eq!(adaptive_interval(None, at(12), true), DAY_INTERVAL * 2);
+        assert_eq!(adaptive_interval(None, at(23), true), MAX_INTERVAL);
+    }
+}
@@ -0,0 +1,385 @@
+// SPDX-License-Identifier: MIT
+
+//! MET Norway weather symbols, as listed at
+//! <https://api.met.no/weatherapi/weathericon/2.0/documentation>.
+
+use crate::fl;
+use std::fmt;
+use std::str::FromStr;
+
+/// Light conditions a symbol is drawn for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    Day,
+    Night,
+    PolarTwilight,
+}
+
+impl Variant {
+    /// The suffix of symbol codes drawn for this variant, e.g. `night`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Day => "day",
+            Self::Night => "night",
+            Self::PolarTwilight => "polartwilight",
+        }
+    }
+}
+
+/// A symbol code such as `rainshowers_day` or `heavysleetandthunder`. Clear
+/// skies and showers come in a day, night and polar twilight variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolCode {
+    ClearSky(Variant),
+    Fair(Variant),
+    PartlyCloudy(Variant),
+    Cloudy,
+    Fog,
+    LightRainShowers(Variant),
+    RainShowers(Variant),
+    HeavyRainShowers(Variant),
+    LightRainShowersAndThunder(Variant),
+    RainShowersAndThunder(Variant),
+    HeavyRainShowersAndThunder(Variant),
+    LightSleetShowers(Variant),
+    SleetShowers(Variant),
+    HeavySleetShowers(Variant),
+    LightSleetShowersAndThunder(Variant),
+    SleetShowersAndThunder(Variant),
+    HeavySleetShowersAndThunder(Variant),
+    LightSnowShowers(Variant),
+    SnowShowers(Variant),
+    HeavySnowShowers(Variant),
+    LightSnowShowersAndThunder(Variant),
+    SnowShowersAndThunder(Variant),
+    HeavySnowShowersAndThunder(Variant),
+    LightRain,
+    Rain,
+    HeavyRain,
+    LightRainAndThunder,
+    RainAndThunder,
+    HeavyRainAndThunder,
+    LightSleet,
+    Sleet,
+    HeavySleet,
+    LightSleetAndThunder,
+    SleetAndThunder,
+    HeavySleetAndThunder,
+    LightSnow,
+    Snow,
+    HeavySnow,
+    LightSnowAndThunder,
+    SnowAndThunder,
+    HeavySnowAndThunder,
+}
+
+/// A symbol code that isn't in MET Norway's list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownSymbol(pub String);
+
+impl SymbolCode {
+    /// The code without its variant, spelled as MET Norway sends it.
+    pub fn condition(self) -> &'static str {
+        match self {
+            Self::ClearSky(_) => "clearsky",
+            Self::Fair(_) => "fair",
+            Self::PartlyCloudy(_) => "partlycloudy",
+            Self::Cloudy => "cloudy",
+            Self::Fog => "fog",
+            Self::LightRainShowers(_) => "lightrainshowers",
+            Self::RainShowers(_) => "rainshowers",
+            Self::HeavyRainShowers(_) => "heavyrainshowers",
+            Self::LightRainShowersAndThunder(_) => "lightrainshowersandthunder",
+            Self::RainShowersAndThunder(_) => "rainshowersandthunder",
+            Self::HeavyRainShowersAndThunder(_) => "heavyrainshowersandthunder",
+            Self::LightSleetShowers(_) => "lightsleetshowers",
+            Self::SleetShowers(_) => "sleetshowers",
+            Self::HeavySleetShowers(_) => "heavysleetshowers",
+            // Sic, these two are misspelled in the API
+            Self::LightSleetShowersAndThunder(_) => "lightssleetshowersandthunder",
+            Self::SleetShowersAndThunder(_) => "sleetshowersandthunder",
+            Self::HeavySleetShowersAndThunder(_) => "heavysleetshowersandthunder",
+            Self::LightSnowShowers(_) => "lightsnowshowers",
+            Self::SnowShowers(_) => "snowshowers",
+            Self::HeavySnowShowers(_) => "heavysnowshowers",
+            Self::LightSnowShowersAndThunder(_) => "lightssnowshowersandthunder",
+            Self::SnowShowersAndThunder(_) => "snowshowersandthunder",
+            Self::HeavySnowShowersAndThunder(_) => "heavysnowshowersandthunder",
+            Self::LightRain => "lightrain",
+            Self::Rain => "rain",
+            Self::HeavyRain => "heavyrain",
+            Self::LightRainAndThunder => "lightrainandthunder",
+            Self::RainAndThunder => "rainandthunder",
+            Self::HeavyRainAndThunder => "heavyrainandthunder",
+            Self::LightSleet => "lightsleet",
+            Self::Sleet => "sleet",
+            Self::HeavySleet => "heavysleet",
+            Self::LightSleetAndThunder => "lightsleetandthunder",
+            Self::SleetAndThunder => "sleetandthunder",
+            Self::HeavySleetAndThunder => "heavysleetandthunder",
+            Self::LightSnow => "lightsnow",
+            Self::Snow => "snow",
+            Self::HeavySnow => "heavysnow",
+            Self::LightSnowAndThunder => "lightsnowandthunder",
+            Self::SnowAndThunder => "snowandthunder",
+            Self::HeavySnowAndThunder => "heavysnowandthunder",
+        }
+    }
+
+    pub fn variant(self) -> Option<Variant> {
+        match self {
+            Self::ClearSky(variant)
+            | Self::Fair(variant)
+            | Self::PartlyCloudy(variant)
+            | Self::LightRainShowers(variant)
+            | Self::RainShowers(variant)
+            | Self::HeavyRainShowers(variant)
+            | Self::LightRainShowersAndThunder(variant)
+            | Self::RainShowersAndThunder(variant)
+            | Self::HeavyRainShowersAndThunder(variant)
+            | Self::LightSleetShowers(variant)
+            | Self::SleetShowers(variant)
+            | Self::HeavySleetShowers(variant)
+            | Self::LightSleetShowersAndThunder(variant)
+            | Self::SleetShowersAndThunder(variant)
+            | Self::HeavySleetShowersAndThunder(variant)
+            | Self::LightSnowShowers(variant)
+            | Self::SnowShowers(variant)
+            | Self::HeavySnowShowers(variant)
+            | Self::LightSnowShowersAndThunder(variant)
+            | Self::SnowShowersAndThunder(variant)
+            | Self::HeavySnowShowersAndThunder(variant) => Some(variant),
+            _ => None,
+        }
+    }
+
+    /// Localized description of the weather, the same for every variant.
+    pub fn description(self) -> String {
+        match self {
+            Self::ClearSky(_) => fl!("symbol-clearsky"),
+            Self::Fair(_) => fl!("symbol-fair"),
+            Self::PartlyCloudy(_) => fl!("symbol-partlycloudy"),
+            Self::Cloudy => fl!("symbol-cloudy"),
+            Self::Fog => fl!("symbol-fog"),
+            Self::LightRainShowers(_) => fl!("symbol-lightrainshowers"),
+            Self::RainShowers(_) => fl!("symbol-rainshowers"),
+            Self::HeavyRainShowers(_) => fl!("symbol-heavyrainshowers"),
+            Self::LightRainShowersAndThunder(_) => fl!("symbol-lightrainshowersandthunder"),
+            Self::RainShowersAndThunder(_) => fl!("symbol-rainshowersandthunder"),
+            Self::HeavyRainShowersAndThunder(_) => fl!("symbol-heavyrainshowersandthunder"),
+            Self::LightSleetShowers(_) => fl!("symbol-lightsleetshowers"),
+            Self::SleetShowers(_) => fl!("symbol-sleetshowers"),
+            Self::HeavySleetShowers(_) => fl!("symbol-heavysleetshowers"),
+            Self::LightSleetShowersAndThunder(_) => fl!("symbol-lightsleetshowersandthunder"),
+            Self::SleetShowersAndThunder(_) => fl!("symbol-sleetshowersandthunder"),
+            Self::HeavySleetShowersAndThunder(_) => fl!("symbol-heavysleetshowersandthunder"),
+            Self::LightSnowShowers(_) => fl!("symbol-lightsnowshowers"),
+            Self::SnowShowers(_) => fl!("symbol-snowshowers"),
+            Self::HeavySnowShowers(_) => fl!("symbol-heavysnowshowers"),
+            Self::LightSnowShowersAndThunder(_) => fl!("symbol-lightsnowshowersandthunder"),
+            Self::SnowShowersAndThunder(_) => fl!("symbol-snowshowersandthunder"),
+            Self::HeavySnowShowersAndThunder(_) => fl!("symbol-heavysnowshowersandthunder"),
+            Self::LightRain => fl!("symbol-lightrain"),
+            Self::Rain => fl!("symbol-rain"),
+            Self::HeavyRain => fl!("symbol-heavyrain"),
+            Self::LightRainAndThunder => fl!("symbol-lightrainandthunder"),
+            Self::RainAndThunder => fl!("symbol-rainandthunder"),
+            Self::HeavyRainAndThunder => fl!("symbol-heavyrainandthunder"),
+            Self::LightSleet => fl!("symbol-lightsleet"),
+            Self::Sleet => fl!("symbol-sleet"),
+            Self::HeavySleet => fl!("symbol-heavysleet"),
+            Self::LightSleetAndThunder => fl!("symbol-lightsleetandthunder"),
+            Self::SleetAndThunder => fl!("symbol-sleetandthunder"),
+            Self::HeavySleetAndThunder => fl!("symbol-heavysleetandthunder"),
+            Self::LightSnow => fl!("symbol-lightsnow"),
+            Self::Snow => fl!("symbol-snow"),
+            Self::HeavySnow => fl!("symbol-heavysnow"),
+            Self::LightSnowAndThunder => fl!("symbol-lightsnowandthunder"),
+            Self::SnowAndThunder => fl!("symbol-snowandthunder"),
+            Self::HeavySnowAndThunder => fl!("symbol-heavysnowandthunder"),
+        }
+    }
+
+    /// The OpenWeatherMap style icon code the applet draws, e.g. "09n".
+    pub fn icon(self) -> String {
+        let condition = self.condition();
+        let icon = match self {
+            Self::ClearSky(_) => "01",
+            Self::Fair(_) | Self::PartlyCloudy(_) => "02",
+            Self::Cloudy => "03",
+            Self::Fog => "50",
+            _ if condition.ends_with("andthunder") => "11",
+            _ if condition.contains("snow") => "13",
+            _ if condition.contains("showers") || condition.contains("sleet") => "09",
+            _ => "10",
+        };
+        let time = if self.variant() == Some(Variant::Night) { "n" } else { "d" };
+        format!("{}{}", icon, time)
+    }
+}
+
+impl FromStr for SymbolCode {
+    type Err = UnknownSymbol;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        let (condition, variant) = match code.split_once('_') {
+            Some((condition, "day")) => (condition, Some(Variant::Day)),
+            Some((condition, "night")) => (condition, Some(Variant::Night)),
+            Some((condition, "polartwilight")) => (condition, Some(Variant::PolarTwilight)),
+            Some(_) => return Err(UnknownSymbol(code.to_string())),
+            None => (code, None),
+        };
+
+        let symbol = match (condition, variant) {
+            ("clearsky", Some(v)) => Self::ClearSky(v),
+            ("fair", Some(v)) => Self::Fair(v),
+            ("partlycloudy", Some(v)) => Self::PartlyCloudy(v),
+            ("cloudy", None) => Self::Cloudy,
+            ("fog", None) => Self::Fog,
+            ("lightrainshowers", Some(v)) => Self::LightRainShowers(v),
+            ("rainshowers", Some(v)) => Self::RainShowers(v),
+            ("heavyrainshowers", Some(v)) => Self::HeavyRainShowers(v),
+            ("lightrainshowersandthunder", Some(v)) => Self::LightRainShowersAndThunder(v),
+            ("rainshowersandthunder", Some(v)) => Self::RainShowersAndThunder(v),
+            ("heavyrainshowersandthunder", Some(v)) => Self::HeavyRainShowersAndThunder(v),
+            ("lightsleetshowers", Some(v)) => Self::LightSleetShowers(v),
+            ("sleetshowers", Some(v)) => Self::SleetShowers(v),
+            ("heavysleetshowers", Some(v)) => Self::HeavySleetShowers(v),
+            // Also accept the correct spelling, in case the API gets fixed
+            ("lightssleetshowersandthunder" | "lightsleetshowersandthunder", Some(v)) => {
+                Self::LightSleetShowersAndThunder(v)
+            }
+            ("sleetshowersandthunder", Some(v)) => Self::SleetShowersAndThunder(v),
+            ("heavysleetshowersandthunder", Some(v)) => Self::HeavySleetShowersAndThunder(v),
+            ("lightsnowshowers", Some(v)) => Self::LightSnowShowers(v),
+            ("snowshowers", Some(v)) => Self::SnowShowers(v),
+            ("heavysnowshowers", Some(v)) => Self::HeavySnowShowers(v),
+            ("lightssnowshowersandthunder" | "lightsnowshowersandthunder", Some(v)) => {
+                Self::LightSnowShowersAndThunder(v)
+            }
+            ("snowshowersandthunder", Some(v)) => Self::SnowShowersAndThunder(v),
+            ("heavysnowshowersandthunder", Some(v)) => Self::HeavySnowShowersAndThunder(v),
+            ("lightrain", None) => Self::LightRain,
+            ("rain", None) => Self::Rain,
+            ("heavyrain", None) => Self::HeavyRain,
+            ("lightrainandthunder", None) => Self::LightRainAndThunder,
+            ("rainandthunder", None) => Self::RainAndThunder,
+            ("heavyrainandthunder", None) => Self::HeavyRainAndThunder,
+            ("lightsleet", None) => Self::LightSleet,
+            ("sleet", None) => Self::Sleet,
+            ("heavysleet", None) => Self::HeavySleet,
+            ("lightsleetandthunder", None) => Self::LightSleetAndThunder,
+            ("sleetandthunder", None) => Self::SleetAndThunder,
+            ("heavysleetandthunder", None) => Self::HeavySleetAndThunder,
+            ("lightsnow", None) => Self::LightSnow,
+            ("snow", None) => Self::Snow,
+            ("heavysnow", None) => Self::HeavySnow,
+            ("lightsnowandthunder", None) => Self::LightSnowAndThunder,
+            ("snowandthunder", None) => Self::SnowAndThunder,
+            ("heavysnowandthunder", None) => Self::HeavySnowAndThunder,
+            _ => return Err(UnknownSymbol(code.to_string())),
+        };
+        Ok(symbol)
+    }
+}
+
+impl fmt::Display for SymbolCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.variant() {
+            Some(variant) => write!(f, "{}_{}", self.condition(), variant.name()),
+            None => f.write_str(self.condition()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The conditions of the symbol list, and whether they have variants
+    const DOCUMENTED: [(&str, bool); 41] = [
+        ("clearsky", true),
+        ("fair", true),
+        ("partlycloudy", true),
+        ("cloudy", false),
+        ("rainshowers", true),
+        ("rainshowersandthunder", true),
+        ("sleetshowers", true),
+        ("snowshowers", true),
+        ("rain", false),
+        ("heavyrain", false),
+        ("heavyrainandthunder", false),
+        ("sleet", false),
+        ("snow", false),
+        ("snowandthunder", false),
+        ("fog", false),
+        ("sleetshowersandthunder", true),
+        ("snowshowersandthunder", true),
+        ("rainandthunder", false),
+        ("sleetandthunder", false),
+        ("lightrainshowersandthunder", true),
+        ("heavyrainshowersandthunder", true),
+        ("lightssleetshowersandthunder", true),
+        ("heavysleetshowersandthunder", true),
+        ("lightssnowshowersandthunder", true),
+        ("heavysnowshowersandthunder", true),
+        ("lightrainandthunder", false),
+        ("lightsleetandthunder", false),
+        ("heavysleetandthunder", false),
+        ("lightsnowandthunder", false),
+        ("heavysnowandthunder", false),
+        ("lightrainshowers", true),
+        ("heavyrainshowers", true),
+        ("lightsleetshowers", true),
+        ("heavysleetshowers", true),
+        ("lightsnowshowers", true),
+        ("heavysnowshowers", true),
+        ("lightrain", false),
+        ("lightsleet", false),
+        ("heavysleet", false),
+        ("lightsnow", false),
+        ("heavysnow", false),
+    ];
+
+    #[test]
+    fn documented_codes_round_trip() {
+        let codes = DOCUMENTED.iter().flat_map(|&(condition, variants)| {
+            if variants {
+                ["day", "night", "polartwilight"].map(|v| format!("{}_{}", condition, v)).to_vec()
+            } else {
+                vec![condition.to_string()]
+            }
+        });
+
+        for code in codes {
+            let symbol: SymbolCode = code.parse().unwrap_or_else(|_| panic!("{} should parse", code));
+            assert_eq!(symbol.to_string(), code);
+        }
+    }
+
+    #[test]
+    fn unknown_codes_are_rejected() {
+        assert!("cloudy_day".parse::<SymbolCode>().is_err());
+        assert!("clearsky".parse::<SymbolCode>().is_err());
+        assert!("rainshowers_evening".parse::<SymbolCode>().is_err());
+        assert_eq!(
+            "hail".parse::<SymbolCode>(),
+            Err(UnknownSymbol("hail".to_string()))
+        );
+    }
+
+    #[test]
+    fn descriptions_and_icons() {
+        let symbol: SymbolCode = "heavysleetandthunder".parse().unwrap();
+        assert_eq!(symbol.description(), "Heavy sleet and thunder");
+        assert_eq!(symbol.icon(), "11d");
+
+        let symbol: SymbolCode = "lightsnowshowers_night".parse().unwrap();
+        assert_eq!(symbol.description(), "Light snow showers");
+        assert_eq!(symbol.icon(), "13n");
+        assert_eq!(
+            "lightsleetshowersandthunder_day".parse(),
+            Ok(SymbolCode::LightSleetShowersAndThunder(Variant::Day))
+        );
+    }
+}
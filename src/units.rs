@@ -0,0 +1,314 @@
+// SPDX-License-Identifier: MIT
+
+//! Unit systems for displaying weather data.
+//!
+//! Providers always report SI-ish units (°C, m/s, hPa, mm); conversion only
+//! happens when formatting for display.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum TemperatureUnit {
+    #[default]
+    Celsius,
+    Fahrenheit,
+    Kelvin,
+}
+
+impl TemperatureUnit {
+    pub const ALL: [TemperatureUnit; 3] = [Self::Celsius, Self::Fahrenheit, Self::Kelvin];
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Celsius => "°C",
+            Self::Fahrenheit => "°F",
+            Self::Kelvin => "K",
+        }
+    }
+
+    pub fn convert(self, celsius: f64) -> f64 {
+        match self {
+            Self::Celsius => celsius,
+            Self::Fahrenheit => celsius * 9.0 / 5.0 + 32.0,
+            Self::Kelvin => celsius + 273.15,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum WindSpeedUnit {
+    #[default]
+    MetersPerSecond,
+    KilometersPerHour,
+    MilesPerHour,
+    Knots,
+    Beaufort,
+}
+
+impl WindSpeedUnit {
+    pub const ALL: [WindSpeedUnit; 5] = [
+        Self::MetersPerSecond,
+        Self::KilometersPerHour,
+        Self::MilesPerHour,
+        Self::Knots,
+        Self::Beaufort,
+    ];
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::MetersPerSecond => "m/s",
+            Self::KilometersPerHour => "km/h",
+            Self::MilesPerHour => "mph",
+            Self::Knots => "kn",
+            Self::Beaufort => "Bft",
+        }
+    }
+
+    pub fn convert(self, meters_per_second: f64) -> f64 {
+        match self {
+            Self::MetersPerSecond => meters_per_second,
+            Self::KilometersPerHour => meters_per_second * 3.6,
+            Self::MilesPerHour => meters_per_second / 0.44704,
+            Self::Knots => meters_per_second * 3600.0 / 1852.0,
+            Self::Beaufort => f64::from(crate::meteo::beaufort(meters_per_second)),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum PressureUnit {
+    #[default]
+    Hectopascal,
+    InchesOfMercury,
+    MillimetersOfMercury,
+}
+
+impl PressureUnit {
+    pub const ALL: [PressureUnit; 3] = [
+        Self::Hectopascal,
+        Self::InchesOfMercury,
+        Self::MillimetersOfMercury,
+    ];
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Hectopascal => "hPa",
+            Self::InchesOfMercury => "inHg",
+            Self::MillimetersOfMercury => "mmHg",
+        }
+    }
+
+    pub fn convert(self, hectopascal: f64) -> f64 {
+        match self {
+            Self::Hectopascal => hectopascal,
+            Self::InchesOfMercury => hectopascal / 33.8639,
+            Self::MillimetersOfMercury => hectopascal / 1.333224,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum PrecipitationUnit {
+    #[default]
+    Millimeters,
+    Inches,
+}
+
+impl PrecipitationUnit {
+    pub const ALL: [PrecipitationUnit; 2] = [Self::Millimeters, Self::Inches];
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Millimeters => "mm",
+            Self::Inches => "in",
+        }
+    }
+
+    pub fn convert(self, millimeters: f64) -> f64 {
+        match self {
+            Self::Millimeters => millimeters,
+            Self::Inches => millimeters / 25.4,
+        }
+    }
+}
+
+/// The unit system chosen in the config, before per-quantity overrides.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Units {
+    #[default]
+    Metric,
+    Imperial,
+    Kelvin,
+}
+
+impl Units {
+    pub const ALL: [Units; 3] = [Self::Metric, Self::Imperial, Self::Kelvin];
+
+    /// Units stored as 'metric', 'imperial' or 'kelvin' by version 2 configs,
+    /// metric for anything else.
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "imperial" => Self::Imperial,
+            "kelvin" => Self::Kelvin,
+            _ => Self::Metric,
+        }
+    }
+
+    pub fn system(self) -> UnitSystem {
+        match self {
+            Self::Metric => UnitSystem::METRIC,
+            Self::Imperial => UnitSystem::IMPERIAL,
+            Self::Kelvin => UnitSystem::KELVIN,
+        }
+    }
+}
+
+/// Units chosen to replace those of the unit system, per quantity.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct UnitOverrides {
+    pub temperature: Option<TemperatureUnit>,
+    pub wind_speed: Option<WindSpeedUnit>,
+    pub pressure: Option<PressureUnit>,
+    pub precipitation: Option<PrecipitationUnit>,
+}
+
+/// The unit for each displayed quantity, with the formatting for it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct UnitSystem {
+    pub temperature: TemperatureUnit,
+    pub wind_speed: WindSpeedUnit,
+    pub pressure: PressureUnit,
+    pub precipitation: PrecipitationUnit,
+}
+
+impl UnitSystem {
+    pub const METRIC: UnitSystem = UnitSystem {
+        temperature: TemperatureUnit::Celsius,
+        wind_speed: WindSpeedUnit::MetersPerSecond,
+        pressure: PressureUnit::Hectopascal,
+        precipitation: PrecipitationUnit::Millimeters,
+    };
+
+    pub const IMPERIAL: UnitSystem = UnitSystem {
+        temperature: TemperatureUnit::Fahrenheit,
+        wind_speed: WindSpeedUnit::MilesPerHour,
+        pressure: PressureUnit::InchesOfMercury,
+        precipitation: PrecipitationUnit::Inches,
+    };
+
+    pub const KELVIN: UnitSystem = UnitSystem {
+        temperature: TemperatureUnit::Kelvin,
+        ..Self::METRIC
+    };
+
+    pub fn with_overrides(self, overrides: &UnitOverrides) -> Self {
+        Self {
+            temperature: overrides.temperature.unwrap_or(self.temperature),
+            wind_speed: overrides.wind_speed.unwrap_or(self.wind_speed),
+            pressure: overrides.pressure.unwrap_or(self.pressure),
+            precipitation: overrides.precipitation.unwrap_or(self.precipitation),
+        }
+    }
+
+    /// A temperature with its unit, e.g. "12°C" or "285 K".
+    pub fn temperature(&self, celsius: f64) -> String {
+        let value = self.temperature.convert(celsius).round() as i32;
+        match self.temperature {
+            TemperatureUnit::Kelvin => format!("{} K", value),
+            unit => format!("{}{}", value, unit.symbol()),
+        }
+    }
+
+    /// A temperature for compact lists where the unit is implied, e.g. "12°".
+    pub fn temperature_short(&self, celsius: f64) -> String {
+        let value = self.temperature.convert(celsius).round() as i32;
+        match self.temperature {
+            TemperatureUnit::Kelvin => format!("{} K", value),
+            _ => format!("{}°", value),
+        }
+    }
+
+    pub fn wind_speed(&self, meters_per_second: f64) -> String {
+        format!(
+            "{:.0} {}",
+            self.wind_speed.convert(meters_per_second),
+            self.wind_speed.symbol()
+        )
+    }
+
+    pub fn pressure(&self, hectopascal: f64) -> String {
+        let value = self.pressure.convert(hectopascal);
+        match self.pressure {
+            PressureUnit::InchesOfMercury => format!("{:.2} {}", value, self.pressure.symbol()),
+            unit => format!("{:.0} {}", value, unit.symbol()),
+        }
+    }
+
+    pub fn precipitation(&self, millimeters: f64) -> String {
+        let value = self.precipitation.convert(millimeters);
+        match self.precipitation {
+            PrecipitationUnit::Inches => format!("{:.2} {}", value, self.precipitation.symbol()),
+            unit => format!("{:.1} {}", value, unit.symbol()),
+        }
+    }
+
+    /// A height above ground, in feet along with inches of precipitation and
+    /// in meters otherwise.
+    pub fn height(&self, meters: f64) -> String {
+        match self.precipitation {
+            PrecipitationUnit::Inches => format!("{:.0} ft", meters / 0.3048),
+            PrecipitationUnit::Millimeters => format!("{:.0} m", meters),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_temperatures() {
+        assert_eq!(UnitSystem::METRIC.temperature(21.4), "21°C");
+        assert_eq!(UnitSystem::IMPERIAL.temperature(100.0), "212°F");
+        assert_eq!(UnitSystem::IMPERIAL.temperature(-40.0), "-40°F");
+        assert_eq!(UnitSystem::KELVIN.temperature(0.0), "273 K");
+        assert_eq!(UnitSystem::IMPERIAL.temperature_short(20.0), "68°");
+    }
+
+    #[test]
+    fn converts_wind_speeds() {
+        let speed = |unit| UnitSystem { wind_speed: unit, ..UnitSystem::METRIC }.wind_speed(10.0);
+        assert_eq!(speed(WindSpeedUnit::MetersPerSecond), "10 m/s");
+        assert_eq!(speed(WindSpeedUnit::KilometersPerHour), "36 km/h");
+        assert_eq!(speed(WindSpeedUnit::MilesPerHour), "22 mph");
+        assert_eq!(speed(WindSpeedUnit::Knots), "19 kn");
+        assert_eq!(speed(WindSpeedUnit::Beaufort), "5 Bft");
+    }
+
+    #[test]
+    fn converts_pressure_and_precipitation() {
+        assert_eq!(UnitSystem::METRIC.pressure(1013.25), "1013 hPa");
+        assert_eq!(UnitSystem::IMPERIAL.pressure(1013.25), "29.92 inHg");
+        let mmhg = UnitSystem { pressure: PressureUnit::MillimetersOfMercury, ..UnitSystem::METRIC };
+        assert_eq!(mmhg.pressure(1013.25), "760 mmHg");
+
+        assert_eq!(UnitSystem::METRIC.precipitation(2.54), "2.5 mm");
+        assert_eq!(UnitSystem::IMPERIAL.precipitation(25.4), "1.00 in");
+
+        assert_eq!(UnitSystem::METRIC.height(625.0), "625 m");
+        assert_eq!(UnitSystem::IMPERIAL.height(625.0), "2051 ft");
+    }
+
+    #[test]
+    fn overrides_replace_single_quantities() {
+        let overrides = UnitOverrides {
+            wind_speed: Some(WindSpeedUnit::KilometersPerHour),
+            ..Default::default()
+        };
+        let units = Units::from_name("imperial").system().with_overrides(&overrides);
+
+        assert_eq!(units.temperature, TemperatureUnit::Fahrenheit);
+        assert_eq!(units.wind_speed, WindSpeedUnit::KilometersPerHour);
+        assert_eq!(Units::from_name("unknown"), Units::Metric);
+    }
+}
@@ -0,0 +1,90 @@
+// SPDX-License-Identifier: MIT
+
+//! Power source from UPower over D-Bus.
+
+use futures_util::{Stream, StreamExt};
+
+#[zbus::proxy(
+    interface = "org.freedesktop.UPower",
+    default_service = "org.freedesktop.UPower",
+    default_path = "/org/freedesktop/UPower"
+)]
+trait UPower {
+    #[zbus(property)]
+    fn on_battery(&self) -> zbus::Result<bool>;
+}
+
+/// Streams whether the system runs on battery, starting with the current state.
+pub async fn on_battery_changes(
+    connection: &zbus::Connection,
+) -> zbus::Result<impl Stream<Item = bool> + use<>> {
+    let upower = UPowerProxy::new(connection).await?;
+    let changes = upower.receive_on_battery_changed().await;
+    let current = upower.on_battery().await?;
+
+    let changes = changes.filter_map(|change| async move { change.get().await.ok() });
+    Ok(futures_util::stream::once(async move { current }).chain(changes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATH: &str = "/org/freedesktop/UPower";
+
+    struct MockUPower {
+        on_battery: bool,
+    }
+
+    #[zbus::interface(name = "org.freedesktop.UPower")]
+    impl MockUPower {
+        #[zbus(property)]
+        fn on_battery(&self) -> bool {
+            self.on_battery
+        }
+    }
+
+    async fn next(changes: &mut (impl Stream<Item = bool> + Unpin)) -> Option<bool> {
+        tokio::time::timeout(std::time::Duration::from_secs(5), changes.next())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn follows_power_source() {
+        let (service, client) = tokio::net::UnixStream::pair().unwrap();
+        let service = zbus::connection::Builder::unix_stream(service)
+            .server(zbus::Guid::generate())
+            .unwrap()
+            .p2p()
+            .serve_at(PATH, MockUPower { on_battery: false })
+            .unwrap()
+            .build();
+        let client = zbus::connection::Builder::unix_stream(client).p2p().build();
+        let (service, connection) = tokio::try_join!(service, client).unwrap();
+
+        let mut changes = Box::pin(on_battery_changes(&connection).await.unwrap());
+        assert_eq!(next(&mut changes).await, Some(false));
+
+        // Unplug
+        let iface = service
+            .object_server()
+            .interface::<_, MockUPower>(PATH)
+            .await
+            .unwrap();
+        iface.get_mut().await.on_battery = true;
+        iface
+            .get()
+            .await
+            .on_battery_changed(iface.signal_emitter())
+            .await
+            .unwrap();
+
+        // The property cache may repeat the initial state first
+        let mut on_battery = next(&mut changes).await;
+        if on_battery == Some(false) {
+            on_battery = next(&mut changes).await;
+        }
+        assert_eq!(on_battery, Some(true));
+    }
+}
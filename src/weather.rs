@@ -1,184 +1,517 @@
 // SPDX-License-Identifier: MIT
 
+mod bright_sky;
+mod error;
+mod met_norway;
+mod nws;
+mod open_meteo;
+mod openweathermap;
+
+use chrono::{DateTime, Local, NaiveDate, Timelike};
+use crate::alerts::Alert;
+use crate::meteo::{self, PressureTrend};
+use crate::symbol::SymbolCode;
+use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Local};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::LazyLock;
+use std::time::{Duration, SystemTime};
+
+pub use bright_sky::BrightSky;
+pub use error::{Retries, WeatherError, MAX_RETRY_ATTEMPTS};
+pub use met_norway::MetNorway;
+pub use nws::Nws;
+pub use open_meteo::OpenMeteo;
+pub use openweathermap::OpenWeatherMap;
+
+/// Identifies the applet to the weather services, as MET Norway's terms require.
+const USER_AGENT: &str = concat!(
+    "cosmic-weather/",
+    env!("CARGO_PKG_VERSION"),
+    " ",
+    env!("CARGO_PKG_REPOSITORY")
+);
+
+/// Locations fetched at the same time, e.g. for the compare page.
+const MAX_CONCURRENT_FETCHES: usize = 3;
+
+/// HTTP client shared by all services, so connections are reused between fetches.
+pub(crate) static HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(Duration::from_secs(30))
+        .build()
+        .expect("HTTP client configuration should be valid")
+});
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct WeatherData {
     pub temperature: f64,
-    pub feels_like: f64, // For MET Norway, this might be the same as temperature
-    pub humidity: u8,
+    pub feels_like: f64,
+    pub humidity: Option<u8>, // in percent
     pub description: String,
     pub icon: String,
+    pub uv_index: Option<f64>,
+    pub dew_point: Option<f64>,
+    pub wind_speed: Option<f64>, // m/s
+    pub wind_direction: Option<f64>, // degrees the wind comes from
+    pub wind_gust: Option<f64>,
+    pub pressure: Option<f64>, // hPa at sea level
+    pub pressure_trend: Option<PressureTrend>, // forecast for the next three hours
+    pub cloud_layers: Option<CloudLayers>,
     pub location: String,
-    pub timestamp: std::time::SystemTime,
+    pub timestamp: SystemTime,
 }
 
-// MET Norway API structures
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-pub struct Geometry {
-    pub r#type: String,
-    pub coordinates: Vec<f64>,
+/// Cloud cover per altitude band, in percent.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct CloudLayers {
+    pub low: Option<f64>,
+    pub medium: Option<f64>,
+    pub high: Option<f64>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-pub struct Units {
-    pub air_pressure_at_sea_level: Option<String>,
-    pub air_temperature: Option<String>,
-    pub air_temperature_max: Option<String>,
-    pub air_temperature_min: Option<String>,
-    pub cloud_area_fraction: Option<String>,
-    pub precipitation_amount: Option<String>,
-    pub relative_humidity: Option<String>,
-    pub wind_from_direction: Option<String>,
-    pub wind_speed: Option<String>,
+/// A single step of the hourly forecast.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HourlyForecast {
+    pub time: DateTime<Local>,
+    pub temperature: f64,
+    pub description: String,
+    pub icon: String,
+    pub precipitation: f64, // in mm over the following hour
+    pub precipitation_probability: Option<u8>, // in percent
+    pub wind_speed: f64,    // in m/s
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-pub struct Meta {
-    pub updated_at: DateTime<Local>,
-    pub units: Units,
+/// Summary of a single local calendar day.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DailyForecast {
+    pub date: NaiveDate,
+    pub temperature_min: f64,
+    pub temperature_max: f64,
+    pub description: String,
+    pub icon: String,
+    pub precipitation: f64, // total in mm
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-pub struct Details {
-    pub air_pressure_at_sea_level: Option<f64>,
-    pub air_temperature: Option<f64>,
-    pub air_temperature_max: Option<f64>,
-    pub air_temperature_min: Option<f64>,
-    pub cloud_area_fraction: Option<f64>,
-    pub relative_humidity: Option<f64>,
-    pub wind_from_direction: Option<f64>,
-    pub wind_speed: Option<f64>,
+/// Normalized forecast for one location, as produced by every provider.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Forecast {
+    pub current: WeatherData,
+    pub hourly: Vec<HourlyForecast>,
+    pub daily: Vec<DailyForecast>,
+    /// When the source allows fetching again, if it says so.
+    pub expires: Option<SystemTime>,
+    /// Alerts issued by the source itself for the location, most severe first.
+    pub alerts: Vec<Alert>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-pub struct Summary {
-    pub symbol_code: String, // This will be used for weather icon
-}
+impl Forecast {
+    /// Up to `hours` hourly steps from `now` on, starting with the current one.
+    pub fn next_hours(&self, now: DateTime<Local>, hours: usize) -> impl Iterator<Item = &HourlyForecast> {
+        self.hourly
+            .iter()
+            .skip_while(move |h| h.time + chrono::Duration::hours(1) <= now)
+            .take(hours)
+    }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-pub struct Next1Hour {
-    pub summary: Summary,
-    pub details: Option<Details>,
+    /// Summary of the current local day, if the source still lists it.
+    pub fn today(&self) -> Option<&DailyForecast> {
+        let today = Local::now().date_naive();
+        self.daily.iter().find(|d| d.date == today)
+    }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-pub struct Instant {
-    pub details: Details,
+/// Weather data sources the applet can fetch from.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Provider {
+    #[default]
+    MetNorway,
+    OpenMeteo,
+    Nws,
+    BrightSky,
+    OpenWeatherMap,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-pub struct Data {
-    pub instant: Instant,
-    pub next_1_hours: Option<Next1Hour>,
+impl Provider {
+    pub const ALL: &'static [Provider] = &[
+        Provider::MetNorway,
+        Provider::OpenMeteo,
+        Provider::Nws,
+        Provider::BrightSky,
+        Provider::OpenWeatherMap,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Provider::MetNorway => "MET Norway",
+            Provider::OpenMeteo => "Open-Meteo",
+            Provider::Nws => "National Weather Service (US)",
+            Provider::BrightSky => "Bright Sky (DWD)",
+            Provider::OpenWeatherMap => "OpenWeatherMap",
+        }
+    }
+
+    /// Whether the service only answers requests carrying the user's API key.
+    pub fn needs_api_key(self) -> bool {
+        matches!(self, Provider::OpenWeatherMap)
+    }
+
+    /// Whether the service's forecasts include severe weather alerts.
+    pub fn has_alerts(self) -> bool {
+        matches!(self, Provider::Nws | Provider::OpenWeatherMap)
+    }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-pub struct Timeseries {
-    pub time: DateTime<Local>,
-    pub data: Data,
+/// Provider specific settings taken from the user configuration.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ProviderOptions {
+    /// Use MET Norway's `complete` endpoint instead of `compact`.
+    pub met_complete: bool,
+    /// Key for the providers that need one.
+    pub api_key: Option<String>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-pub struct Properties {
-    pub meta: Meta,
-    pub timeseries: Vec<Timeseries>,
+/// A source of weather data.
+///
+/// Implementations fetch current conditions together with the hourly and daily
+/// forecast and normalize them into a [`Forecast`].
+pub trait WeatherProvider {
+    fn fetch_forecast(
+        &self,
+        lat: f64,
+        lon: f64,
+    ) -> impl Future<Output = Result<Forecast, WeatherError>> + Send;
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
-pub struct MetWeatherResponse {
-    pub r#type: String,
-    pub geometry: Geometry,
-    pub properties: Properties,
+pub async fn get_weather_data(
+    provider: Provider,
+    options: ProviderOptions,
+    lat: f64,
+    lon: f64,
+) -> Result<Forecast, WeatherError> {
+    let mut forecast = match provider {
+        Provider::MetNorway => {
+            MetNorway { complete: options.met_complete }.fetch_forecast(lat, lon).await
+        }
+        Provider::OpenMeteo => OpenMeteo.fetch_forecast(lat, lon).await,
+        Provider::Nws => Nws.fetch_forecast(lat, lon).await,
+        Provider::BrightSky => BrightSky.fetch_forecast(lat, lon).await,
+        Provider::OpenWeatherMap => match options.api_key {
+            Some(api_key) => OpenWeatherMap { api_key }.fetch_forecast(lat, lon).await,
+            None => Err(WeatherError::MissingApiKey),
+        },
+    }?;
+    derive_dew_point(&mut forecast.current);
+    Ok(forecast)
 }
 
-pub async fn get_weather_data(lat: f64, lon: f64) -> Result<WeatherData, Box<dyn std::error::Error>> {
-    let url = format!(
-        "https://api.met.no/weatherapi/locationforecast/2.0/compact?lat={}&lon={}",
-        lat, lon
-    );
+// Not every source reports the dew point, but it follows from the humidity
+fn derive_dew_point(weather: &mut WeatherData) {
+    if weather.dew_point.is_none()
+        && let Some(humidity) = weather.humidity.filter(|humidity| *humidity > 0)
+    {
+        weather.dew_point = Some(meteo::dew_point(weather.temperature, f64::from(humidity)));
+    }
+}
 
-    let client = reqwest::Client::new();
-    let response = client
-        .get(&url)
-        .header("User-Agent", "cosmic-weather/1.0.0") // Required by MET Norway API
-        .send()
-        .await?;
+/// Asks the providers of `chain` in order until one answers, returning the
+/// forecast together with the provider it came from. When none answers, the
+/// error of the first one is returned, as that's the provider the user picked.
+pub async fn fetch_with_fallback<F, Fut>(chain: &[Provider], fetch: F) -> Result<(Provider, Forecast), WeatherError>
+where
+    F: Fn(Provider) -> Fut,
+    Fut: Future<Output = Result<Forecast, WeatherError>>,
+{
+    let mut first_error = None;
+    for &provider in chain {
+        match fetch(provider).await {
+            Ok(forecast) => return Ok((provider, forecast)),
+            Err(err) => {
+                eprintln!("Error fetching weather data from {}: {}", provider.name(), err);
+                first_error.get_or_insert(err);
+            }
+        }
+    }
+    Err(first_error.unwrap_or(WeatherError::NoData))
+}
 
-    if response.status().is_success() {
-        let weather_response: MetWeatherResponse = response.json().await?;
+/// Runs `fetch` for every location with bounded parallelism. The results are
+/// in the order of `locations`.
+pub async fn fetch_each<T, F, Fut>(locations: Vec<(f64, f64)>, fetch: F) -> Vec<((f64, f64), T)>
+where
+    F: Fn(f64, f64) -> Fut,
+    Fut: Future<Output = T>,
+{
+    futures_util::stream::iter(locations)
+        .map(|(lat, lon)| {
+            let result = fetch(lat, lon);
+            async move { ((lat, lon), result.await) }
+        })
+        .buffered(MAX_CONCURRENT_FETCHES)
+        .collect()
+        .await
+}
 
-        // Get the first timeseries data (current weather)
-        if let Some(timeseries) = weather_response.properties.timeseries.first() {
-            let details = &timeseries.data.instant.details;
+/// A forecast period that daily summaries are aggregated from.
+struct Period<'a> {
+    start: DateTime<Local>,
+    hours: u32,
+    temperature_min: f64,
+    temperature_max: f64,
+    symbol_code: &'a str,
+    precipitation: f64,
+}
 
-            // Extract weather data
-            let temperature = details.air_temperature.unwrap_or(0.0);
-            let humidity = details.relative_humidity.unwrap_or(0.0) as u8;
-            let description = timeseries.data.next_1_hours.as_ref()
-                .map(|h| h.summary.symbol_code.clone())
-                .unwrap_or_else(|| "clear sky".to_string());
+/// The last forecast fetched from `provider`, without touching the network.
+///
+/// Used to show something right away at startup and while offline.
+pub fn cached_forecast(
+    provider: Provider,
+    options: &ProviderOptions,
+    lat: f64,
+    lon: f64,
+) -> Option<Forecast> {
+    match provider {
+        Provider::MetNorway => MetNorway { complete: options.met_complete }.cached_forecast(lat, lon),
+        Provider::OpenMeteo | Provider::Nws | Provider::BrightSky | Provider::OpenWeatherMap => {
+            None
+        }
+    }
+}
 
-            // Map MET Norway weather codes to common descriptions
-            let description_text = map_weather_code_to_description(&description);
+// Groups periods by the local calendar day they start in and summarizes each day
+fn aggregate_daily(periods: &[Period]) -> Vec<DailyForecast> {
+    let mut days: BTreeMap<NaiveDate, Vec<&Period>> = BTreeMap::new();
+    for period in periods {
+        days.entry(period.start.date_naive()).or_default().push(period);
+    }
 
-            // Map MET Norway weather codes to icon codes
-            let icon = map_weather_code_to_icon(&description);
+    days.into_iter()
+        .map(|(date, periods)| {
+            let code = dominant_symbol_code(&periods);
+            DailyForecast {
+                date,
+                temperature_min: periods.iter().map(|p| p.temperature_min).fold(f64::INFINITY, f64::min),
+                temperature_max: periods.iter().map(|p| p.temperature_max).fold(f64::NEG_INFINITY, f64::max),
+                description: map_weather_code_to_description(code),
+                icon: map_weather_code_to_icon(code),
+                precipitation: periods.iter().map(|p| p.precipitation).sum(),
+            }
+        })
+        .collect()
+}
 
-            let weather_data = WeatherData {
-                temperature,
-                feels_like: temperature, // MET Norway doesn't provide feels_like, using temperature
-                humidity,
-                description: description_text,
-                icon,
-                location: format!("({}, {})", lat, lon), // For now, using coordinates as location
-                timestamp: std::time::SystemTime::now(),
-            };
+// Picks the symbol covering most of the daytime hours, or of the whole day if
+// no period starts during daytime
+fn dominant_symbol_code<'a>(periods: &[&Period<'a>]) -> &'a str {
+    let daytime: Vec<&Period> = periods
+        .iter()
+        .copied()
+        .filter(|p| (6..18).contains(&p.start.hour()))
+        .collect();
+    let candidates = if daytime.is_empty() { periods } else { &daytime };
 
-            Ok(weather_data)
-        } else {
-            Err("No weather data available".into())
+    let mut weights: Vec<(&str, u32)> = Vec::new();
+    for period in candidates {
+        match weights.iter_mut().find(|(code, _)| *code == period.symbol_code) {
+            Some((_, weight)) => *weight += period.hours,
+            None => weights.push((period.symbol_code, period.hours)),
         }
-    } else {
-        Err(format!("API request failed with status: {}", response.status()).into())
     }
+
+    // Prefer the earliest symbol on ties so the result is stable
+    weights
+        .iter()
+        .rev()
+        .max_by_key(|(_, weight)| *weight)
+        .map(|(code, _)| *code)
+        .unwrap_or("clearsky_day")
 }
 
-// Helper function to map MET Norway weather codes to descriptions
+// Localized description of a MET Norway symbol code
 fn map_weather_code_to_description(code: &str) -> String {
-    match code {
-        "clearsky_day" | "clearsky_night" | "clearsky_polartwilight" => "Clear sky".to_string(),
-        "fair_day" | "fair_night" | "fair_polartwilight" => "Fair".to_string(),
-        "cloudy" | "partlycloudy_day" | "partlycloudy_night" | "partlycloudy_polartwilight" => "Cloudy".to_string(),
-        "fog" => "Fog".to_string(),
-        "lightrain" | "rainshowers_day" | "rainshowers_night" | "rainshowers_polartwilight" => "Light rain".to_string(),
-        "rain" | "heavyrain" => "Rain".to_string(),
-        "lightrainshowers_day" | "lightrainshowers_night" | "lightrainshowers_polartwilight" => "Light rain showers".to_string(),
-        "lightsnow" => "Light snow".to_string(),
-        "sleet" => "Sleet".to_string(),
-        "lightsleet" => "Light sleet".to_string(),
-        "thunderstorm" => "Thunderstorm".to_string(),
-        "sleetshowers_day" | "sleetshowers_night" | "sleetshowers_polartwilight" => "Sleet showers".to_string(),
-        "snowshowers_day" | "snowshowers_night" | "snowshowers_polartwilight" => "Snow showers".to_string(),
-        "snow" | "heavysnow" => "Snow".to_string(),
-        _ => "Unknown".to_string(),
-    }
-}
-
-// Helper function to map MET Norway weather codes to icon codes
+    code.parse::<SymbolCode>()
+        .map_or_else(|_| crate::fl!("symbol-unknown"), SymbolCode::description)
+}
+
+// Icon code for a MET Norway symbol code, clear sky for unknown ones
 fn map_weather_code_to_icon(code: &str) -> String {
-    match code {
-        "clearsky_day" => "01d".to_string(),
-        "clearsky_night" => "01n".to_string(),
-        "fair_day" | "partlycloudy_day" => "02d".to_string(),
-        "fair_night" | "partlycloudy_night" => "02n".to_string(),
-        "cloudy" => "03d".to_string(), // Using same for day/night
-        "rain" | "lightrain" | "rainshowers_day" | "rainshowers_night" | "rainshowers_polartwilight" => "10d".to_string(),
-        "snow" | "heavysnow" | "snowshowers_day" | "snowshowers_night" | "snowshowers_polartwilight" => "13d".to_string(),
-        "fog" => "50d".to_string(),
-        "thunderstorm" => "11d".to_string(),
-        "sleet" | "lightsleet" => "09d".to_string(),
-        _ => "01d".to_string(), // Default to clear sky icon
-    }
-}
\ No newline at end of file
+    code.parse::<SymbolCode>()
+        .map_or_else(|_| "01d".to_string(), SymbolCode::icon)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::sync::Mutex;
+
+    fn forecast(temperature: f64) -> Forecast {
+        Forecast {
+            current: WeatherData {
+                temperature,
+                feels_like: temperature,
+                humidity: Some(50),
+                description: "Clear sky".to_string(),
+                icon: "01d".to_string(),
+                uv_index: None,
+                dew_point: None,
+                wind_speed: None,
+                wind_direction: None,
+                wind_gust: None,
+                pressure: None,
+                pressure_trend: None,
+                cloud_layers: None,
+                location: String::new(),
+                timestamp: SystemTime::UNIX_EPOCH,
+            },
+            hourly: Vec::new(),
+            daily: Vec::new(),
+            expires: None,
+            alerts: Vec::new(),
+        }
+    }
+
+    fn period(day: u32, hour: u32, hours: u32, temperature: (f64, f64), symbol_code: &str, precipitation: f64) -> Period<'_> {
+        Period {
+            start: Local.with_ymd_and_hms(2024, 3, day, hour, 0, 0).earliest().unwrap(),
+            hours,
+            temperature_min: temperature.0,
+            temperature_max: temperature.1,
+            symbol_code,
+            precipitation,
+        }
+    }
+
+    #[test]
+    fn days_are_grouped_by_local_date() {
+        let periods = [
+            period(12, 0, 6, (1.0, 3.0), "cloudy", 0.2),
+            period(12, 6, 6, (3.0, 8.0), "rain", 1.5),
+            period(12, 12, 6, (7.0, 11.0), "rain", 2.0),
+            period(12, 18, 6, (4.0, 7.0), "cloudy", 0.0),
+            period(13, 0, 12, (-2.0, 1.0), "snow", 3.0),
+        ];
+
+        let days = aggregate_daily(&periods);
+
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].date, NaiveDate::from_ymd_opt(2024, 3, 12).unwrap());
+        assert_eq!((days[0].temperature_min, days[0].temperature_max), (1.0, 11.0));
+        assert_eq!(days[0].precipitation, 0.2 + 1.5 + 2.0 + 0.0);
+        assert_eq!(days[1].date, NaiveDate::from_ymd_opt(2024, 3, 13).unwrap());
+        assert_eq!((days[1].temperature_min, days[1].temperature_max), (-2.0, 1.0));
+        assert_eq!(days[1].precipitation, 3.0);
+    }
+
+    #[test]
+    fn daytime_symbol_describes_the_day() {
+        // Rain through the night, but a fair day
+        let periods = [
+            period(12, 0, 6, (1.0, 3.0), "rain", 2.0),
+            period(12, 6, 6, (3.0, 8.0), "fair_day", 0.0),
+            period(12, 12, 6, (7.0, 11.0), "fair_day", 0.0),
+            period(12, 18, 6, (4.0, 7.0), "rain", 1.0),
+        ];
+        let days = aggregate_daily(&periods);
+        assert_eq!(days[0].icon, "02d");
+
+        // The symbol covering more hours wins, the earliest on ties
+        let periods = [
+            period(12, 6, 1, (3.0, 3.0), "cloudy", 0.0),
+            period(12, 7, 1, (3.0, 3.0), "rain", 0.5),
+            period(12, 8, 1, (3.0, 3.0), "rain", 0.5),
+            period(12, 9, 1, (3.0, 3.0), "cloudy", 0.0),
+            period(12, 10, 1, (3.0, 3.0), "snow", 0.0),
+        ];
+        let refs: Vec<&Period> = periods.iter().collect();
+        assert_eq!(dominant_symbol_code(&refs), "cloudy");
+
+        // Only night periods left, so they decide
+        let periods = [period(13, 0, 6, (0.0, 1.0), "snow", 1.0)];
+        assert_eq!(aggregate_daily(&periods)[0].icon, "13d");
+    }
+
+    #[tokio::test]
+    async fn first_provider_that_answers_is_used() {
+        let chain = [Provider::MetNorway, Provider::OpenMeteo, Provider::BrightSky];
+        let asked = Mutex::new(Vec::new());
+
+        let (provider, forecast) = fetch_with_fallback(&chain, |provider| {
+            asked.lock().unwrap().push(provider);
+            let result = match provider {
+                Provider::MetNorway => Err(WeatherError::Timeout),
+                _ => Ok(forecast(5.0)),
+            };
+            async move { result }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(provider, Provider::OpenMeteo);
+        assert_eq!(forecast.current.temperature, 5.0);
+        // Fallbacks after the one that answered aren't asked
+        assert_eq!(*asked.lock().unwrap(), vec![Provider::MetNorway, Provider::OpenMeteo]);
+    }
+
+    #[tokio::test]
+    async fn error_of_the_selected_provider_is_reported() {
+        let chain = [Provider::OpenWeatherMap, Provider::OpenMeteo];
+
+        let result = fetch_with_fallback(&chain, |provider| {
+            let error = match provider {
+                Provider::OpenWeatherMap => WeatherError::Forbidden(401),
+                _ => WeatherError::Server(503),
+            };
+            async move { Err(error) }
+        })
+        .await;
+
+        assert_eq!(result.err(), Some(WeatherError::Forbidden(401)));
+    }
+
+    #[test]
+    fn dew_point_is_derived_from_the_humidity() {
+        let mut weather = forecast(20.0).current;
+        derive_dew_point(&mut weather);
+        assert_eq!(weather.dew_point, Some(meteo::dew_point(20.0, 50.0)));
+
+        // A reported dew point is kept
+        weather.dew_point = Some(3.0);
+        derive_dew_point(&mut weather);
+        assert_eq!(weather.dew_point, Some(3.0));
+
+        // Without a humidity there is nothing to derive
+        weather.dew_point = None;
+        weather.humidity = None;
+        derive_dew_point(&mut weather);
+        assert_eq!(weather.dew_point, None);
+    }
+
+    #[tokio::test]
+    async fn locations_are_answered_in_their_order() {
+        let locations = vec![(59.91, 10.75), (-29.16, -51.18), (40.71, -74.01), (52.52, 13.4)];
+
+        let results = fetch_each(locations, |lat, _| async move {
+            // The first location answers last
+            if lat == 59.91 {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+            if lat == 40.71 { Err(WeatherError::Timeout) } else { Ok(lat) }
+        })
+        .await;
+
+        // One failing location leaves the others
+        assert_eq!(
+            results,
+            vec![
+                ((59.91, 10.75), Ok(59.91)),
+                ((-29.16, -51.18), Ok(-29.16)),
+                ((40.71, -74.01), Err(WeatherError::Timeout)),
+                ((52.52, 13.4), Ok(52.52)),
+            ]
+        );
+    }
+}
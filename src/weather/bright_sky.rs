@@ -0,0 +1,372 @@
+// SPDX-License-Identifier: MIT
+
+//! Bright Sky backend, serving the open data of Germany's Deutscher
+//! Wetterdienst (DWD): station observations and MOSMIX forecasts.
+
+use crate::symbol::{SymbolCode, Variant};
+use super::{
+    aggregate_daily, map_weather_code_to_description, map_weather_code_to_icon, Forecast,
+    HourlyForecast, Period, WeatherData, WeatherError, WeatherProvider, HTTP_CLIENT,
+};
+use chrono::{DateTime, Local};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+const BASE_URL: &str = "https://api.brightsky.dev";
+
+/// Days of hourly forecast requested, covering the daily list in the popup.
+const FORECAST_DAYS: i64 = 9;
+
+// Bright Sky API structures, in its default DWD units (°C, km/h, hPa, mm)
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct CurrentWeatherResponse {
+    pub weather: CurrentWeather,
+}
+
+/// Latest observations, with winds averaged over the last 10 minutes.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct CurrentWeather {
+    pub timestamp: DateTime<Local>,
+    pub temperature: Option<f64>,
+    pub relative_humidity: Option<f64>,
+    pub dew_point: Option<f64>,
+    pub pressure_msl: Option<f64>,
+    pub wind_speed_10: Option<f64>,
+    pub wind_direction_10: Option<f64>,
+    pub wind_gust_speed_10: Option<f64>,
+    pub precipitation_60: Option<f64>,
+    pub cloud_cover: Option<f64>,
+    pub condition: Option<String>,
+    pub icon: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct WeatherResponse {
+    pub weather: Vec<WeatherRecord>,
+}
+
+/// One hour of forecast, or of observations for hours already past.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct WeatherRecord {
+    pub timestamp: DateTime<Local>,
+    pub temperature: Option<f64>,
+    pub precipitation: Option<f64>,
+    pub precipitation_probability: Option<u8>,
+    pub wind_speed: Option<f64>,
+    pub cloud_cover: Option<f64>,
+    pub condition: Option<String>,
+    pub icon: Option<String>,
+}
+
+/// Fetches observations and forecasts from api.brightsky.dev, which covers
+/// Germany and its surroundings. No API key is required.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BrightSky;
+
+impl WeatherProvider for BrightSky {
+    async fn fetch_forecast(
+        &self,
+        lat: f64,
+        lon: f64,
+    ) -> Result<Forecast, WeatherError> {
+        let lat = format!("{:.4}", lat);
+        let lon = format!("{:.4}", lon);
+        let today = Local::now().date_naive();
+        let date = today.to_string();
+        let last_date = (today + chrono::Duration::days(FORECAST_DAYS)).to_string();
+
+        let current_url = format!("{}/current_weather", BASE_URL);
+        let weather_url = format!("{}/weather", BASE_URL);
+        let current_query = [("lat", &lat), ("lon", &lon)];
+        let weather_query = [("lat", &lat), ("lon", &lon), ("date", &date), ("last_date", &last_date)];
+        let result = futures_util::try_join!(
+            get_json::<CurrentWeatherResponse>(&current_url, &current_query),
+            get_json::<WeatherResponse>(&weather_url, &weather_query),
+        );
+        let (current, weather) = match result {
+            // No station or forecast point near the location
+            Err(WeatherError::Http(404)) => return Err(WeatherError::NoData),
+            result => result?,
+        };
+
+        normalize(&current, &weather, &lat, &lon)
+    }
+}
+
+async fn get_json<T: DeserializeOwned>(url: &str, query: &[(&str, &String)]) -> Result<T, WeatherError> {
+    let response = HTTP_CLIENT.get(url).query(query).send().await?;
+
+    if response.status().is_success() {
+        Ok(response.json().await?)
+    } else {
+        Err(WeatherError::from_status(response.status(), response.headers()))
+    }
+}
+
+// Converts the Bright Sky responses into the provider independent model
+fn normalize(
+    current: &CurrentWeatherResponse,
+    weather: &WeatherResponse,
+    lat: &str,
+    lon: &str,
+) -> Result<Forecast, WeatherError> {
+    let current = &current.weather;
+    let Some(temperature) = current.temperature else {
+        return Err(WeatherError::NoData);
+    };
+    let wind_speed = current.wind_speed_10.map(meters_per_second);
+    let feels_like = match (current.relative_humidity, wind_speed) {
+        (Some(humidity), Some(wind_speed)) => crate::meteo::feels_like(temperature, humidity, wind_speed),
+        _ => temperature,
+    };
+    let code = symbol_code(
+        current.icon.as_deref(),
+        current.condition.as_deref(),
+        current.precipitation_60,
+        current.cloud_cover,
+    )
+    .to_string();
+
+    let current = WeatherData {
+        temperature,
+        feels_like,
+        humidity: current.relative_humidity.map(|humidity| humidity as u8),
+        description: map_weather_code_to_description(&code),
+        icon: map_weather_code_to_icon(&code),
+        uv_index: None,
+        dew_point: current.dew_point,
+        wind_speed,
+        wind_direction: current.wind_direction_10,
+        wind_gust: current.wind_gust_speed_10.map(meters_per_second),
+        pressure: current.pressure_msl,
+        pressure_trend: None,
+        cloud_layers: None,
+        location: format!("({}, {})", lat, lon),
+        timestamp: std::time::SystemTime::now(),
+    };
+
+    let records: Vec<(&WeatherRecord, f64, String)> = weather
+        .weather
+        .iter()
+        .filter_map(|record| {
+            let code = symbol_code(
+                record.icon.as_deref(),
+                record.condition.as_deref(),
+                record.precipitation,
+                record.cloud_cover,
+            )
+            .to_string();
+            Some((record, record.temperature?, code))
+        })
+        .collect();
+
+    let hourly = records
+        .iter()
+        .map(|(record, temperature, code)| HourlyForecast {
+            time: record.timestamp,
+            temperature: *temperature,
+            description: map_weather_code_to_description(code),
+            icon: map_weather_code_to_icon(code),
+            precipitation: record.precipitation.unwrap_or(0.0),
+            precipitation_probability: record.precipitation_probability,
+            wind_speed: record.wind_speed.map_or(0.0, meters_per_second),
+        })
+        .collect();
+
+    let periods: Vec<Period> = records
+        .iter()
+        .map(|(record, temperature, code)| Period {
+            start: record.timestamp,
+            hours: 1,
+            temperature_min: *temperature,
+            temperature_max: *temperature,
+            symbol_code: code,
+            precipitation: record.precipitation.unwrap_or(0.0),
+        })
+        .collect();
+    let daily = aggregate_daily(&periods);
+
+    Ok(Forecast {
+        current,
+        hourly,
+        daily,
+        expires: None,
+        alerts: Vec::new(),
+    })
+}
+
+fn meters_per_second(kilometers_per_hour: f64) -> f64 {
+    kilometers_per_hour / 3.6
+}
+
+/// Precipitation in mm per hour below which it counts as light, DWD's limit.
+const LIGHT_PRECIPITATION: f64 = 2.5;
+
+/// Precipitation in mm per hour from which it counts as heavy.
+const HEAVY_PRECIPITATION: f64 = 10.0;
+
+// Maps Bright Sky's `icon`, `condition` and hourly `precipitation` to a MET
+// Norway symbol. The condition names the precipitation and its amount the
+// intensity. Bright Sky only shows a precipitation icon once it rains
+// steadily, so precipitation under a clear or partly cloudy icon falls as
+// showers, by day or night as the icon tells.
+fn symbol_code(
+    icon: Option<&str>,
+    condition: Option<&str>,
+    precipitation: Option<f64>,
+    cloud_cover: Option<f64>,
+) -> SymbolCode {
+    let variant = match icon {
+        Some(icon) if icon.ends_with("-night") => Some(Variant::Night),
+        Some(icon) if icon.ends_with("-day") => Some(Variant::Day),
+        _ => None,
+    };
+
+    let (kind, thunder) = match condition {
+        Some("thunderstorm") => ("rain", "andthunder"),
+        Some("rain") => ("rain", ""),
+        Some("sleet" | "hail") => ("sleet", ""),
+        Some("snow") => ("snow", ""),
+        Some("fog") => return SymbolCode::Fog,
+        _ => return sky_symbol_code(icon, variant.unwrap_or(Variant::Day), cloud_cover),
+    };
+    let intensity = match precipitation {
+        Some(amount) if amount >= HEAVY_PRECIPITATION => "heavy",
+        Some(amount) if amount >= LIGHT_PRECIPITATION => "",
+        _ => "light",
+    };
+    let code = match variant {
+        Some(variant) => format!("{}{}showers{}_{}", intensity, kind, thunder, variant.name()),
+        None => format!("{}{}{}", intensity, kind, thunder),
+    };
+    // Every combination above is one of MET Norway's codes
+    code.parse().unwrap_or(SymbolCode::Cloudy)
+}
+
+// Symbol for a sky without precipitation
+fn sky_symbol_code(icon: Option<&str>, variant: Variant, cloud_cover: Option<f64>) -> SymbolCode {
+    match icon {
+        Some("clear-day" | "clear-night") | None => SymbolCode::ClearSky(variant),
+        Some("partly-cloudy-day" | "partly-cloudy-night") => SymbolCode::PartlyCloudy(variant),
+        Some("fog") => SymbolCode::Fog,
+        // The wind icon hides the sky, which the cloud cover in percent tells
+        Some("wind") => match cloud_cover {
+            Some(cover) if cover < 12.5 => SymbolCode::ClearSky(variant),
+            Some(cover) if cover < 50.0 => SymbolCode::Fair(variant),
+            Some(cover) if cover < 87.5 => SymbolCode::PartlyCloudy(variant),
+            _ => SymbolCode::Cloudy,
+        },
+        _ => SymbolCode::Cloudy,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn forecast() -> Forecast {
+        let current = serde_json::from_str(include_str!("../../tests/fixtures/bright_sky_current_weather.json"))
+            .expect("fixture should deserialize");
+        let weather = serde_json::from_str(include_str!("../../tests/fixtures/bright_sky_weather.json"))
+            .expect("fixture should deserialize");
+        normalize(&current, &weather, "52.5200", "13.4050").unwrap()
+    }
+
+    #[test]
+    fn current_observations_are_normalized() {
+        let current = forecast().current;
+
+        assert_eq!(current.temperature, 7.2);
+        assert_eq!(current.humidity, Some(81));
+        assert_eq!(current.dew_point, Some(4.1));
+        assert_eq!(current.pressure, Some(1012.4));
+        assert_eq!(current.wind_speed, Some(5.0));
+        assert_eq!(current.wind_gust, Some(10.0));
+        assert_eq!(current.wind_direction, Some(240.0));
+        assert_eq!(current.description, "Partly cloudy");
+        assert_eq!(current.icon, "02d");
+    }
+
+    #[test]
+    fn hourly_records_keep_their_icons() {
+        let forecast = forecast();
+        let icons: Vec<&str> = forecast.hourly.iter().map(|h| h.icon.as_str()).collect();
+
+        assert_eq!(forecast.hourly.len(), 5);
+        assert_eq!(icons, ["01n", "03d", "10d", "11d", "13d"]);
+        assert_eq!(forecast.hourly[2].precipitation, 1.6);
+        assert_eq!(forecast.hourly[2].precipitation_probability, Some(70));
+        assert_eq!(forecast.hourly[2].wind_speed, 4.0);
+    }
+
+    #[test]
+    fn days_are_aggregated_from_hours() {
+        let forecast = forecast();
+
+        // Hours are grouped by their local date, which depends on the
+        // timezone the tests run in
+        let hours = [
+            ("2024-03-12T03:00:00+00:00", 3.9, 0.0),
+            ("2024-03-12T12:00:00+00:00", 9.4, 0.0),
+            ("2024-03-12T15:00:00+00:00", 8.1, 1.6),
+            ("2024-03-12T18:00:00+00:00", 6.3, 0.8),
+            ("2024-03-13T02:00:00+00:00", 0.4, 0.3),
+        ];
+        let local_date = |time: &str| DateTime::parse_from_rfc3339(time).unwrap().with_timezone(&Local).date_naive();
+        let mut dates: Vec<NaiveDate> = hours.iter().map(|(time, _, _)| local_date(time)).collect();
+        dates.dedup();
+
+        assert_eq!(forecast.daily.iter().map(|d| d.date).collect::<Vec<_>>(), dates);
+        for day in &forecast.daily {
+            let hours: Vec<(f64, f64)> = hours
+                .iter()
+                .filter(|(time, _, _)| local_date(time) == day.date)
+                .map(|(_, temperature, precipitation)| (*temperature, *precipitation))
+                .collect();
+            assert_eq!(day.temperature_min, hours.iter().map(|h| h.0).fold(f64::INFINITY, f64::min));
+            assert_eq!(day.temperature_max, hours.iter().map(|h| h.0).fold(f64::NEG_INFINITY, f64::max));
+            assert_eq!(day.precipitation, hours.iter().map(|h| h.1).sum::<f64>());
+        }
+    }
+
+    #[test]
+    fn showers_keep_the_time_of_day_of_the_icon() {
+        let current = serde_json::from_str(include_str!("../../tests/fixtures/bright_sky_current_weather.json"))
+            .expect("fixture should deserialize");
+        let weather = serde_json::from_str(include_str!("../../tests/fixtures/bright_sky_weather_night.json"))
+            .expect("fixture should deserialize");
+        let forecast = normalize(&current, &weather, "52.5200", "13.4050").unwrap();
+        let symbols: Vec<(&str, &str)> = forecast
+            .hourly
+            .iter()
+            .map(|h| (h.description.as_str(), h.icon.as_str()))
+            .collect();
+
+        assert_eq!(
+            symbols,
+            [
+                ("Light rain showers", "09n"),
+                ("Heavy rain and thunder", "11d"),
+                // Windy, with a quarter of the sky covered
+                ("Fair", "02d"),
+            ]
+        );
+    }
+
+    #[test]
+    fn condition_sets_the_precipitation() {
+        assert_eq!(symbol_code(Some("cloudy"), Some("rain"), Some(3.0), None), SymbolCode::Rain);
+        assert_eq!(symbol_code(Some("rain"), Some("hail"), Some(0.5), None), SymbolCode::LightSleet);
+        assert_eq!(
+            symbol_code(Some("partly-cloudy-day"), Some("thunderstorm"), Some(4.0), None),
+            SymbolCode::RainShowersAndThunder(Variant::Day)
+        );
+        assert_eq!(
+            symbol_code(Some("clear-night"), Some("dry"), Some(0.0), None),
+            SymbolCode::ClearSky(Variant::Night)
+        );
+        assert_eq!(symbol_code(Some("wind"), Some("dry"), None, Some(95.0)), SymbolCode::Cloudy);
+        assert_eq!(symbol_code(None, None, None, None), SymbolCode::ClearSky(Variant::Day));
+    }
+}
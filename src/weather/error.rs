@@ -0,0 +1,303 @@
+// SPDX-License-Identifier: MIT
+
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+/// Delay before the first retry, doubled for every further attempt.
+const RETRY_BASE: Duration = Duration::from_secs(30);
+/// Upper bound for the backoff, before jitter.
+const RETRY_CAP: Duration = Duration::from_secs(15 * 60);
+/// Automatic retries after a failed fetch, before waiting for the next regular refresh.
+pub const MAX_RETRY_ATTEMPTS: u32 = 8;
+
+/// Why fetching weather data failed.
+///
+/// Kept cheap to clone so it can travel inside application messages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WeatherError {
+    /// The service couldn't be reached (DNS, refused connection, TLS).
+    Network(String),
+    /// The service didn't answer in time.
+    Timeout,
+    /// The service refused the request (401/403), e.g. a rejected User-Agent or API key.
+    Forbidden(u16),
+    /// Too many requests (429). The service may say when to try again.
+    RateLimited { retry_after: Option<Duration> },
+    /// The service failed on its side (5xx).
+    Server(u16),
+    /// Any other unexpected HTTP status.
+    Http(u16),
+    /// The response didn't have the expected shape, e.g. after a schema change.
+    Parse(String),
+    /// The response was valid but held no usable data, e.g. an empty timeseries.
+    NoData,
+    /// The provider needs an API key and none is configured.
+    MissingApiKey,
+}
+
+impl WeatherError {
+    /// Classifies an unsuccessful HTTP response.
+    pub fn from_status(status: StatusCode, headers: &HeaderMap) -> Self {
+        match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => WeatherError::Forbidden(status.as_u16()),
+            StatusCode::TOO_MANY_REQUESTS => WeatherError::RateLimited {
+                retry_after: retry_after(headers),
+            },
+            status if status.is_server_error() => WeatherError::Server(status.as_u16()),
+            status => WeatherError::Http(status.as_u16()),
+        }
+    }
+
+    /// Whether the same request may succeed if repeated a bit later.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            WeatherError::Network(_)
+                | WeatherError::Timeout
+                | WeatherError::RateLimited { .. }
+                | WeatherError::Server(_)
+        )
+    }
+
+    /// How long to wait before retry number `attempt` (counting from 0), or
+    /// `None` if retrying won't help.
+    ///
+    /// Uses exponential backoff with jitter so clients that failed together
+    /// don't retry in lockstep. A `Retry-After` from the server is a lower bound.
+    pub fn retry_delay(&self, attempt: u32) -> Option<Duration> {
+        if !self.is_transient() {
+            return None;
+        }
+
+        let backoff = RETRY_BASE
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(RETRY_CAP);
+        // Keep half of the delay and randomize the other half
+        let half = backoff / 2;
+        let delay = half + half.mul_f64(fastrand::f64());
+
+        match self {
+            WeatherError::RateLimited { retry_after: Some(after) } => Some(delay.max(*after)),
+            _ => Some(delay),
+        }
+    }
+}
+
+/// Automatic retries made since the last successful or newly requested fetch.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Retries {
+    attempt: u32,
+}
+
+impl Retries {
+    /// Retries made so far, 0 before the first.
+    pub fn attempt(self) -> u32 {
+        self.attempt
+    }
+
+    /// Starts over, e.g. after a success or when a fetch is requested anew.
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+
+    /// Counts another retry after `error` and returns how long to wait for
+    /// it, or `None` if the error is permanent or the retries are used up.
+    pub fn next(&mut self, error: &WeatherError) -> Option<Duration> {
+        if self.attempt >= MAX_RETRY_ATTEMPTS {
+            return None;
+        }
+        let delay = error.retry_delay(self.attempt)?;
+        self.attempt += 1;
+        Some(delay)
+    }
+}
+
+// `Retry-After` is either a number of seconds or an HTTP date
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    SystemTime::from(date).duration_since(SystemTime::now()).ok()
+}
+
+impl fmt::Display for WeatherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WeatherError::Network(reason) => write!(f, "network error: {}", reason),
+            WeatherError::Timeout => write!(f, "request timed out"),
+            WeatherError::Forbidden(status) => write!(f, "request refused with status {}", status),
+            WeatherError::RateLimited { retry_after: Some(after) } => {
+                write!(f, "rate limited, retry after {}s", after.as_secs())
+            }
+            WeatherError::RateLimited { retry_after: None } => write!(f, "rate limited"),
+            WeatherError::Server(status) => write!(f, "server error with status {}", status),
+            WeatherError::Http(status) => write!(f, "API request failed with status: {}", status),
+            WeatherError::Parse(reason) => write!(f, "unexpected response: {}", reason),
+            WeatherError::NoData => write!(f, "no weather data available"),
+            WeatherError::MissingApiKey => write!(f, "no API key configured"),
+        }
+    }
+}
+
+impl std::error::Error for WeatherError {}
+
+impl From<reqwest::Error> for WeatherError {
+    fn from(err: reqwest::Error) -> Self {
+        // The URL can hold secrets, e.g. OpenWeatherMap's `appid`, and errors end up in logs
+        let err = err.without_url();
+        if err.is_timeout() {
+            WeatherError::Timeout
+        } else if err.is_decode() {
+            WeatherError::Parse(err.to_string())
+        } else if let Some(status) = err.status() {
+            WeatherError::from_status(status, &HeaderMap::new())
+        } else {
+            WeatherError::Network(err.to_string())
+        }
+    }
+}
+
+impl From<serde_json::Error> for WeatherError {
+    fn from(err: serde_json::Error) -> Self {
+        WeatherError::Parse(err.to_string())
+    }
+}
+
+impl From<chrono::ParseError> for WeatherError {
+    fn from(err: chrono::ParseError) -> Self {
+        WeatherError::Parse(err.to_string())
+    }
+}
+
+impl From<quick_xml::DeError> for WeatherError {
+    fn from(err: quick_xml::DeError) -> Self {
+        WeatherError::Parse(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn retry_after_header(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn statuses_are_classified() {
+        let none = HeaderMap::new();
+
+        assert_eq!(WeatherError::from_status(StatusCode::UNAUTHORIZED, &none), WeatherError::Forbidden(401));
+        assert_eq!(WeatherError::from_status(StatusCode::FORBIDDEN, &none), WeatherError::Forbidden(403));
+        assert_eq!(
+            WeatherError::from_status(StatusCode::TOO_MANY_REQUESTS, &none),
+            WeatherError::RateLimited { retry_after: None }
+        );
+        assert_eq!(
+            WeatherError::from_status(StatusCode::TOO_MANY_REQUESTS, &retry_after_header("30")),
+            WeatherError::RateLimited { retry_after: Some(Duration::from_secs(30)) }
+        );
+        assert_eq!(WeatherError::from_status(StatusCode::INTERNAL_SERVER_ERROR, &none), WeatherError::Server(500));
+        assert_eq!(WeatherError::from_status(StatusCode::SERVICE_UNAVAILABLE, &none), WeatherError::Server(503));
+        assert_eq!(WeatherError::from_status(StatusCode::NOT_FOUND, &none), WeatherError::Http(404));
+        assert_eq!(WeatherError::from_status(StatusCode::IM_A_TEAPOT, &none), WeatherError::Http(418));
+    }
+
+    #[test]
+    fn only_temporary_failures_are_transient() {
+        assert!(WeatherError::Network("connection refused".to_string()).is_transient());
+        assert!(WeatherError::Timeout.is_transient());
+        assert!(WeatherError::RateLimited { retry_after: None }.is_transient());
+        assert!(WeatherError::Server(502).is_transient());
+
+        assert!(!WeatherError::Forbidden(403).is_transient());
+        assert!(!WeatherError::Http(404).is_transient());
+        assert!(!WeatherError::Parse("missing field".to_string()).is_transient());
+        assert!(!WeatherError::NoData.is_transient());
+        assert!(!WeatherError::MissingApiKey.is_transient());
+    }
+
+    #[test]
+    fn backoff_doubles_per_attempt_with_jitter() {
+        for _ in 0..100 {
+            for attempt in 0..4 {
+                let full = RETRY_BASE * 2u32.pow(attempt);
+                let delay = WeatherError::Timeout.retry_delay(attempt).unwrap();
+                assert!(delay >= full / 2 && delay <= full, "attempt {}: {:?}", attempt, delay);
+            }
+        }
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        for attempt in [5, 10, 40, u32::MAX] {
+            let delay = WeatherError::Server(503).retry_delay(attempt).unwrap();
+            assert!(delay >= RETRY_CAP / 2 && delay <= RETRY_CAP, "attempt {}: {:?}", attempt, delay);
+        }
+    }
+
+    #[test]
+    fn permanent_errors_are_not_retried() {
+        assert_eq!(WeatherError::Forbidden(403).retry_delay(0), None);
+        assert_eq!(WeatherError::Http(404).retry_delay(0), None);
+        assert_eq!(WeatherError::Parse("missing field".to_string()).retry_delay(0), None);
+        assert_eq!(WeatherError::NoData.retry_delay(0), None);
+        assert_eq!(WeatherError::MissingApiKey.retry_delay(0), None);
+    }
+
+    #[test]
+    fn retries_stop_when_used_up_until_reset() {
+        let mut retries = Retries::default();
+        for attempt in 1..=MAX_RETRY_ATTEMPTS {
+            assert!(retries.next(&WeatherError::Timeout).is_some());
+            assert_eq!(retries.attempt(), attempt);
+        }
+
+        assert_eq!(retries.next(&WeatherError::Timeout), None);
+        assert_eq!(retries.attempt(), MAX_RETRY_ATTEMPTS);
+
+        // A new fetch gets the full number of retries again
+        retries.reset();
+        assert!(retries.next(&WeatherError::Timeout).is_some());
+        assert_eq!(retries.attempt(), 1);
+    }
+
+    #[test]
+    fn permanent_errors_dont_count_as_retries() {
+        let mut retries = Retries::default();
+        assert_eq!(retries.next(&WeatherError::Forbidden(403)), None);
+        assert_eq!(retries.attempt(), 0);
+    }
+
+    #[test]
+    fn retry_after_is_a_lower_bound() {
+        let after = Duration::from_secs(2 * 60 * 60);
+        let error = WeatherError::RateLimited { retry_after: Some(after) };
+        assert_eq!(error.retry_delay(0), Some(after));
+
+        // A shorter wait than the backoff doesn't shorten it
+        let error = WeatherError::RateLimited { retry_after: Some(Duration::from_secs(1)) };
+        assert!(error.retry_delay(0).unwrap() >= RETRY_BASE / 2);
+    }
+
+    #[test]
+    fn retry_after_is_read_in_both_formats() {
+        assert_eq!(retry_after(&retry_after_header("120")), Some(Duration::from_secs(120)));
+
+        let in_an_hour = chrono::Utc::now() + chrono::Duration::hours(1);
+        let date = in_an_hour.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+        let delay = retry_after(&retry_after_header(&date)).unwrap();
+        assert!(delay > Duration::from_secs(3590) && delay <= Duration::from_secs(3600), "{:?}", delay);
+
+        assert_eq!(retry_after(&retry_after_header("Wed, 21 Oct 2015 07:28:00 GMT")), None);
+        assert_eq!(retry_after(&retry_after_header("soon")), None);
+        assert_eq!(retry_after(&HeaderMap::new()), None);
+    }
+}
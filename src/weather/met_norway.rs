@@ -0,0 +1,704 @@
+// SPDX-License-Identifier: MIT
+
+//! MET Norway Locationforecast 2.0 backend.
+
+use crate::cache;
+use super::{
+    aggregate_daily, map_weather_code_to_description, map_weather_code_to_icon, CloudLayers,
+    Forecast, HourlyForecast, Period, WeatherData, WeatherError, WeatherProvider, HTTP_CLIENT,
+};
+use chrono::{DateTime, Local};
+use reqwest::header::{HeaderMap, HeaderName, EXPIRES, IF_MODIFIED_SINCE, LAST_MODIFIED};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::SystemTime;
+
+// MET Norway API structures, covering every field of the `complete` endpoint.
+// The `compact` endpoint returns a subset, so everything is optional.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Geometry {
+    pub r#type: String,
+    pub coordinates: Vec<f64>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Units {
+    pub air_pressure_at_sea_level: Option<String>,
+    pub air_temperature: Option<String>,
+    pub air_temperature_max: Option<String>,
+    pub air_temperature_min: Option<String>,
+    pub air_temperature_percentile_10: Option<String>,
+    pub air_temperature_percentile_90: Option<String>,
+    pub cloud_area_fraction: Option<String>,
+    pub cloud_area_fraction_high: Option<String>,
+    pub cloud_area_fraction_low: Option<String>,
+    pub cloud_area_fraction_medium: Option<String>,
+    pub dew_point_temperature: Option<String>,
+    pub fog_area_fraction: Option<String>,
+    pub precipitation_amount: Option<String>,
+    pub precipitation_amount_max: Option<String>,
+    pub precipitation_amount_min: Option<String>,
+    pub probability_of_precipitation: Option<String>,
+    pub probability_of_thunder: Option<String>,
+    pub relative_humidity: Option<String>,
+    pub ultraviolet_index_clear_sky: Option<String>,
+    pub wind_from_direction: Option<String>,
+    pub wind_speed: Option<String>,
+    pub wind_speed_of_gust: Option<String>,
+    pub wind_speed_percentile_10: Option<String>,
+    pub wind_speed_percentile_90: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Meta {
+    pub updated_at: DateTime<Local>,
+    pub units: Units,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Details {
+    pub air_pressure_at_sea_level: Option<f64>,
+    pub air_temperature: Option<f64>,
+    pub air_temperature_max: Option<f64>,
+    pub air_temperature_min: Option<f64>,
+    pub air_temperature_percentile_10: Option<f64>,
+    pub air_temperature_percentile_90: Option<f64>,
+    pub cloud_area_fraction: Option<f64>,
+    pub cloud_area_fraction_high: Option<f64>,
+    pub cloud_area_fraction_low: Option<f64>,
+    pub cloud_area_fraction_medium: Option<f64>,
+    pub dew_point_temperature: Option<f64>,
+    pub fog_area_fraction: Option<f64>,
+    pub precipitation_amount: Option<f64>,
+    pub precipitation_amount_max: Option<f64>,
+    pub precipitation_amount_min: Option<f64>,
+    pub probability_of_precipitation: Option<f64>,
+    pub probability_of_thunder: Option<f64>,
+    pub relative_humidity: Option<f64>,
+    pub ultraviolet_index_clear_sky: Option<f64>,
+    pub wind_from_direction: Option<f64>,
+    pub wind_speed: Option<f64>,
+    pub wind_speed_of_gust: Option<f64>,
+    pub wind_speed_percentile_10: Option<f64>,
+    pub wind_speed_percentile_90: Option<f64>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Summary {
+    pub symbol_code: String, // This will be used for weather icon
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Next1Hour {
+    pub summary: Summary,
+    pub details: Option<Details>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Next6Hours {
+    pub summary: Summary,
+    pub details: Option<Details>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Next12Hours {
+    pub summary: Summary,
+    pub details: Option<Details>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Instant {
+    pub details: Details,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Data {
+    pub instant: Instant,
+    pub next_1_hours: Option<Next1Hour>,
+    pub next_6_hours: Option<Next6Hours>,
+    pub next_12_hours: Option<Next12Hours>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Timeseries {
+    pub time: DateTime<Local>,
+    pub data: Data,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Properties {
+    pub meta: Meta,
+    pub timeseries: Vec<Timeseries>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct MetWeatherResponse {
+    pub r#type: String,
+    pub geometry: Geometry,
+    pub properties: Properties,
+}
+
+/// A previously fetched response together with its HTTP caching headers.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct CachedResponse {
+    response: MetWeatherResponse,
+    fetched_at: SystemTime,
+    expires: Option<SystemTime>,
+    last_modified: Option<String>,
+}
+
+impl CachedResponse {
+    fn new(response: MetWeatherResponse, headers: &HeaderMap, now: SystemTime) -> Self {
+        CachedResponse {
+            response,
+            fetched_at: now,
+            expires: header_time(headers, EXPIRES),
+            last_modified: header_string(headers, LAST_MODIFIED),
+        }
+    }
+
+    /// Whether the response may be used without asking the API again.
+    fn is_fresh(&self, now: SystemTime) -> bool {
+        self.expires.is_some_and(|expires| expires > now)
+    }
+}
+
+/// Responses by request URL. MET Norway's terms of service require clients to
+/// wait for `Expires` and to revalidate with `If-Modified-Since`.
+///
+/// Entries are also written to disk, so the last forecast survives restarts
+/// and can be shown while offline.
+static CACHE: LazyLock<Mutex<HashMap<String, CachedResponse>>> = LazyLock::new(Default::default);
+
+/// Fetches forecasts from api.met.no. No API key is required.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MetNorway {
+    /// Use the `complete` endpoint, which adds dew point, fog, UV, gusts,
+    /// cloud layers and percentiles to the `compact` set.
+    pub complete: bool,
+}
+
+impl MetNorway {
+    fn endpoint(&self) -> &'static str {
+        if self.complete { "complete" } else { "compact" }
+    }
+
+    // MET Norway asks for at most four decimals, which also keeps the cache key stable
+    fn url(&self, lat: f64, lon: f64) -> String {
+        format!(
+            "https://api.met.no/weatherapi/locationforecast/2.0/{}?lat={:.4}&lon={:.4}",
+            self.endpoint(),
+            lat,
+            lon
+        )
+    }
+
+    fn cache_file_name(&self, lat: f64, lon: f64) -> String {
+        format!("met-{}-{:.4}_{:.4}.json", self.endpoint(), lat, lon)
+    }
+
+    fn load_cached(&self, lat: f64, lon: f64) -> Option<CachedResponse> {
+        let url = self.url(lat, lon);
+        if let Some(cached) = CACHE.lock().ok().and_then(|cache| cache.get(&url).cloned()) {
+            return Some(cached);
+        }
+
+        let cached: CachedResponse = cache::load(&self.cache_file_name(lat, lon))?;
+        if let Ok(mut cache) = CACHE.lock() {
+            cache.insert(url, cached.clone());
+        }
+        Some(cached)
+    }
+
+    /// The last successfully fetched forecast, from memory or disk, without
+    /// touching the network.
+    pub fn cached_forecast(&self, lat: f64, lon: f64) -> Option<Forecast> {
+        to_forecast(&self.load_cached(lat, lon)?, lat, lon).ok()
+    }
+}
+
+impl WeatherProvider for MetNorway {
+    async fn fetch_forecast(
+        &self,
+        lat: f64,
+        lon: f64,
+    ) -> Result<Forecast, WeatherError> {
+        let url = self.url(lat, lon);
+        let cached = self.load_cached(lat, lon);
+
+        // Don't hit the API again before the previous response expires
+        if let Some(fresh) = cached.as_ref().filter(|c| c.is_fresh(SystemTime::now())) {
+            return to_forecast(fresh, lat, lon);
+        }
+
+        let mut request = HTTP_CLIENT.get(&url);
+        if let Some(last_modified) = cached.as_ref().and_then(|c| c.last_modified.as_deref()) {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+        let response = request.send().await?;
+
+        let entry = if response.status().is_success() {
+            let headers = response.headers().clone();
+            CachedResponse::new(response.json().await?, &headers, SystemTime::now())
+        } else {
+            revalidate(cached, response.status(), response.headers(), SystemTime::now())?
+        };
+
+        cache::store(&self.cache_file_name(lat, lon), &entry);
+        if let Ok(mut cache) = CACHE.lock() {
+            cache.insert(url, entry.clone());
+        }
+
+        to_forecast(&entry, lat, lon)
+    }
+}
+
+fn to_forecast(
+    entry: &CachedResponse,
+    lat: f64,
+    lon: f64,
+) -> Result<Forecast, WeatherError> {
+    let mut forecast = normalize(&entry.response, lat, lon)?;
+    forecast.current.timestamp = entry.fetched_at;
+    forecast.expires = entry.expires;
+    Ok(forecast)
+}
+
+// The entry to keep after an answer without a forecast: on 304 Not Modified
+// the cached one under the new caching headers, otherwise none
+fn revalidate(
+    cached: Option<CachedResponse>,
+    status: StatusCode,
+    headers: &HeaderMap,
+    now: SystemTime,
+) -> Result<CachedResponse, WeatherError> {
+    if status != StatusCode::NOT_MODIFIED {
+        return Err(WeatherError::from_status(status, headers));
+    }
+    // Only possible if the cache was lost between request and response
+    let Some(cached) = cached else {
+        return Err(WeatherError::Http(status.as_u16()));
+    };
+
+    let mut entry = CachedResponse::new(cached.response, headers, now);
+    entry.last_modified = entry.last_modified.or(cached.last_modified);
+    Ok(entry)
+}
+
+fn header_string(headers: &HeaderMap, name: HeaderName) -> Option<String> {
+    headers.get(name)?.to_str().ok().map(str::to_string)
+}
+
+// HTTP dates use the RFC 2822 format, e.g. "Tue, 12 Mar 2024 13:30:00 GMT"
+fn header_time(headers: &HeaderMap, name: HeaderName) -> Option<SystemTime> {
+    let value = header_string(headers, name)?;
+    DateTime::parse_from_rfc2822(&value).ok().map(SystemTime::from)
+}
+
+// Converts a MET Norway response into the provider independent model
+fn normalize(
+    response: &MetWeatherResponse,
+    lat: f64,
+    lon: f64,
+) -> Result<Forecast, WeatherError> {
+    // Current weather is the latest step that has already started, which is the
+    // first one unless the response came from the cache
+    let now = Local::now();
+    let Some(timeseries) = response
+        .properties
+        .timeseries
+        .iter()
+        .rev()
+        .find(|entry| entry.time <= now)
+        .or(response.properties.timeseries.first())
+    else {
+        return Err(WeatherError::NoData);
+    };
+
+    let details = &timeseries.data.instant.details;
+
+    // Extract weather data
+    let temperature = details.air_temperature.unwrap_or(0.0);
+    // MET Norway doesn't report an apparent temperature, derive it
+    let feels_like = match (details.relative_humidity, details.wind_speed) {
+        (Some(humidity), Some(wind_speed)) => crate::meteo::feels_like(temperature, humidity, wind_speed),
+        _ => temperature,
+    };
+    let code = symbol_code(timeseries);
+
+    // Pressure trend from the forecast for three hours later
+    let later = timeseries.time + chrono::Duration::hours(3);
+    let pressure_trend = response
+        .properties
+        .timeseries
+        .iter()
+        .find(|entry| entry.time == later)
+        .and_then(|entry| entry.data.instant.details.air_pressure_at_sea_level)
+        .zip(details.air_pressure_at_sea_level)
+        .map(|(later, now)| crate::meteo::pressure_trend(now, later));
+
+    let current = WeatherData {
+        temperature,
+        feels_like,
+        humidity: details.relative_humidity.map(|humidity| humidity as u8),
+        description: map_weather_code_to_description(code),
+        icon: map_weather_code_to_icon(code),
+        // The following are only available from the complete endpoint
+        uv_index: details.ultraviolet_index_clear_sky,
+        dew_point: details.dew_point_temperature,
+        wind_speed: details.wind_speed,
+        wind_direction: details.wind_from_direction,
+        wind_gust: details.wind_speed_of_gust,
+        pressure: details.air_pressure_at_sea_level,
+        pressure_trend,
+        cloud_layers: (details.cloud_area_fraction_low.is_some()
+            || details.cloud_area_fraction_medium.is_some()
+            || details.cloud_area_fraction_high.is_some())
+        .then_some(CloudLayers {
+            low: details.cloud_area_fraction_low,
+            medium: details.cloud_area_fraction_medium,
+            high: details.cloud_area_fraction_high,
+        }),
+        location: format!("({}, {})", lat, lon),
+        timestamp: std::time::SystemTime::now(),
+    };
+
+    // Only the first part of the series has hourly resolution
+    let hourly: Vec<HourlyForecast> = response
+        .properties
+        .timeseries
+        .iter()
+        .filter(|entry| entry.data.next_1_hours.is_some())
+        .map(|entry| {
+            let details = &entry.data.instant.details;
+            let code = symbol_code(entry);
+            HourlyForecast {
+                time: entry.time,
+                temperature: details.air_temperature.unwrap_or(0.0),
+                description: map_weather_code_to_description(code),
+                icon: map_weather_code_to_icon(code),
+                precipitation: entry
+                    .data
+                    .next_1_hours
+                    .as_ref()
+                    .and_then(|h| h.details.as_ref())
+                    .and_then(|d| d.precipitation_amount)
+                    .unwrap_or(0.0),
+                precipitation_probability: entry
+                    .data
+                    .next_1_hours
+                    .as_ref()
+                    .and_then(|h| h.details.as_ref())
+                    .and_then(|d| d.probability_of_precipitation)
+                    .map(|p| p.round() as u8),
+                wind_speed: details.wind_speed.unwrap_or(0.0),
+            }
+        })
+        .collect();
+
+    let daily = aggregate_daily(&periods(&response.properties.timeseries));
+
+    Ok(Forecast {
+        current,
+        hourly,
+        daily,
+        expires: None,
+        alerts: Vec::new(),
+    })
+}
+
+// Splits the series into non-overlapping periods, using the finest resolution
+// available: hourly at first, then 6 and 12 hour steps further out
+fn periods(timeseries: &[Timeseries]) -> Vec<Period<'_>> {
+    let mut periods = Vec::with_capacity(timeseries.len());
+    let mut covered_until: Option<DateTime<Local>> = None;
+
+    for entry in timeseries {
+        if covered_until.is_some_and(|until| entry.time < until) {
+            continue;
+        }
+
+        let data = &entry.data;
+        let (hours, summary, details) = if let Some(next) = &data.next_1_hours {
+            (1, &next.summary, next.details.as_ref())
+        } else if let Some(next) = &data.next_6_hours {
+            (6, &next.summary, next.details.as_ref())
+        } else if let Some(next) = &data.next_12_hours {
+            (12, &next.summary, next.details.as_ref())
+        } else {
+            continue;
+        };
+        let Some(temperature) = data.instant.details.air_temperature else {
+            continue;
+        };
+
+        periods.push(Period {
+            start: entry.time,
+            hours,
+            temperature_min: details
+                .and_then(|d| d.air_temperature_min)
+                .map_or(temperature, |min| min.min(temperature)),
+            temperature_max: details
+                .and_then(|d| d.air_temperature_max)
+                .map_or(temperature, |max| max.max(temperature)),
+            symbol_code: &summary.symbol_code,
+            precipitation: details.and_then(|d| d.precipitation_amount).unwrap_or(0.0),
+        });
+        covered_until = Some(entry.time + chrono::Duration::hours(i64::from(hours)));
+    }
+
+    periods
+}
+
+fn symbol_code(timeseries: &Timeseries) -> &str {
+    timeseries
+        .data
+        .next_1_hours
+        .as_ref()
+        .map(|h| h.summary.symbol_code.as_str())
+        .unwrap_or("clearsky_day")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use std::time::Duration;
+
+    fn fixture() -> MetWeatherResponse {
+        serde_json::from_str(include_str!("../../tests/fixtures/met_norway_compact.json"))
+            .expect("fixture should deserialize")
+    }
+
+    fn complete_fixture() -> MetWeatherResponse {
+        serde_json::from_str(include_str!("../../tests/fixtures/met_norway_complete.json"))
+            .expect("fixture should deserialize")
+    }
+
+    fn time(time: &str) -> DateTime<Local> {
+        DateTime::parse_from_rfc3339(time).unwrap().with_timezone(&Local)
+    }
+
+    fn headers(pairs: &[(HeaderName, &str)]) -> HeaderMap {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.clone(), value.parse().unwrap()))
+            .collect()
+    }
+
+    fn cached(expires: Option<SystemTime>, last_modified: Option<&str>) -> CachedResponse {
+        CachedResponse {
+            response: fixture(),
+            fetched_at: SystemTime::UNIX_EPOCH,
+            expires,
+            last_modified: last_modified.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn http_dates_are_parsed() {
+        let headers = headers(&[
+            (EXPIRES, "Tue, 12 Mar 2024 10:30:00 GMT"),
+            (LAST_MODIFIED, "yesterday"),
+        ]);
+
+        assert_eq!(
+            header_time(&headers, EXPIRES),
+            Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1710239400))
+        );
+        assert_eq!(header_time(&headers, LAST_MODIFIED), None);
+        assert_eq!(header_time(&headers, IF_MODIFIED_SINCE), None);
+    }
+
+    #[test]
+    fn responses_are_reused_until_they_expire() {
+        let now = SystemTime::now();
+
+        assert!(cached(Some(now + Duration::from_secs(60)), None).is_fresh(now));
+        assert!(!cached(Some(now), None).is_fresh(now));
+        assert!(!cached(Some(now - Duration::from_secs(60)), None).is_fresh(now));
+        // Without `Expires` every fetch asks the API
+        assert!(!cached(None, None).is_fresh(now));
+    }
+
+    #[test]
+    fn new_responses_keep_their_caching_headers() {
+        let now = SystemTime::now();
+        let headers = headers(&[
+            (EXPIRES, "Tue, 12 Mar 2024 10:30:00 GMT"),
+            (LAST_MODIFIED, "Tue, 12 Mar 2024 09:45:12 GMT"),
+        ]);
+
+        let entry = CachedResponse::new(fixture(), &headers, now);
+
+        assert_eq!(entry.fetched_at, now);
+        assert_eq!(entry.expires, header_time(&headers, EXPIRES));
+        assert_eq!(entry.last_modified.as_deref(), Some("Tue, 12 Mar 2024 09:45:12 GMT"));
+    }
+
+    #[test]
+    fn not_modified_reuses_the_cached_forecast() {
+        let now = SystemTime::now();
+        let previous = cached(None, Some("Tue, 12 Mar 2024 09:45:12 GMT"));
+        let headers = headers(&[(EXPIRES, "Tue, 12 Mar 2024 11:00:00 GMT")]);
+
+        let entry = revalidate(Some(previous.clone()), StatusCode::NOT_MODIFIED, &headers, now).unwrap();
+
+        assert_eq!(entry.response, previous.response);
+        assert_eq!(entry.fetched_at, now);
+        assert_eq!(entry.expires, header_time(&headers, EXPIRES));
+        // The validator is kept when the answer doesn't repeat it
+        assert_eq!(entry.last_modified, previous.last_modified);
+    }
+
+    #[test]
+    fn other_answers_without_a_forecast_are_errors() {
+        let now = SystemTime::now();
+        let none = HeaderMap::new();
+
+        assert_eq!(
+            revalidate(None, StatusCode::NOT_MODIFIED, &none, now).err(),
+            Some(WeatherError::Http(304))
+        );
+        assert_eq!(
+            revalidate(Some(cached(None, None)), StatusCode::SERVICE_UNAVAILABLE, &none, now).err(),
+            Some(WeatherError::Server(503))
+        );
+    }
+
+    #[test]
+    fn cache_entries_survive_the_disk() {
+        let expires = SystemTime::UNIX_EPOCH + Duration::from_secs(1710239400);
+        let entry = cached(Some(expires), Some("Tue, 12 Mar 2024 09:45:12 GMT"));
+
+        // The cache module stores entries as JSON
+        let json = serde_json::to_vec(&entry).unwrap();
+        let loaded: CachedResponse = serde_json::from_slice(&json).unwrap();
+
+        assert_eq!(loaded.response, entry.response);
+        assert_eq!(loaded.fetched_at, entry.fetched_at);
+        assert_eq!(loaded.expires, entry.expires);
+        assert_eq!(loaded.last_modified, entry.last_modified);
+        assert_eq!(
+            to_forecast(&loaded, 59.91, 10.75).unwrap().current.timestamp,
+            SystemTime::UNIX_EPOCH
+        );
+    }
+
+    #[test]
+    fn periods_use_the_finest_resolution() {
+        let response = fixture();
+        let periods = periods(&response.properties.timeseries);
+
+        // The 16:00 step is covered by the six hours from 13:00, the last one has no period
+        let starts: Vec<DateTime<Local>> = periods.iter().map(|p| p.start).collect();
+        assert_eq!(
+            starts,
+            [
+                "2024-03-12T10:00:00Z",
+                "2024-03-12T11:00:00Z",
+                "2024-03-12T12:00:00Z",
+                "2024-03-12T13:00:00Z",
+                "2024-03-12T19:00:00Z",
+                "2024-03-13T06:00:00Z",
+            ]
+            .map(time)
+        );
+        assert_eq!(periods.iter().map(|p| p.hours).collect::<Vec<_>>(), [1, 1, 1, 6, 6, 12]);
+        assert_eq!(
+            periods.iter().map(|p| p.symbol_code).collect::<Vec<_>>(),
+            ["partlycloudy_day", "rain", "rain", "partlycloudy_day", "clearsky_night", "fair_day"]
+        );
+        assert_eq!(
+            periods.iter().map(|p| p.precipitation).collect::<Vec<_>>(),
+            [0.0, 0.5, 1.2, 0.4, 0.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn period_extremes_include_the_instant_temperature() {
+        let response = fixture();
+        let periods = periods(&response.properties.timeseries);
+
+        // Hourly steps have no extremes of their own
+        assert_eq!((periods[0].temperature_min, periods[0].temperature_max), (5.0, 5.0));
+        // 7.5 at 13:00 lies within the forecast 6.0 to 9.0
+        assert_eq!((periods[3].temperature_min, periods[3].temperature_max), (6.0, 9.0));
+        assert_eq!((periods[4].temperature_min, periods[4].temperature_max), (1.5, 4.5));
+        assert_eq!((periods[5].temperature_min, periods[5].temperature_max), (2.0, 2.0));
+    }
+
+    #[test]
+    fn days_cover_every_period() {
+        let forecast = normalize(&fixture(), 59.91, 10.75).unwrap();
+
+        // Grouped by local date, so the number of days depends on the timezone
+        let mut dates: Vec<NaiveDate> = ["2024-03-12T10:00:00Z", "2024-03-12T19:00:00Z", "2024-03-13T06:00:00Z"]
+            .map(|t| time(t).date_naive())
+            .to_vec();
+        dates.dedup();
+        assert_eq!(forecast.daily.iter().map(|d| d.date).collect::<Vec<_>>(), dates);
+
+        let total: f64 = forecast.daily.iter().map(|d| d.precipitation).sum();
+        assert!((total - 2.1).abs() < 1e-9, "{}", total);
+        let min = forecast.daily.iter().map(|d| d.temperature_min).fold(f64::INFINITY, f64::min);
+        let max = forecast.daily.iter().map(|d| d.temperature_max).fold(f64::NEG_INFINITY, f64::max);
+        assert_eq!((min, max), (1.5, 9.0));
+    }
+
+    #[test]
+    fn next_24_hours_start_at_the_current_hour() {
+        let forecast = normalize(&complete_fixture(), 59.91, 10.75).unwrap();
+        let hours: Vec<DateTime<Local>> = forecast
+            .next_hours(time("2024-06-20T10:30:00Z"), 24)
+            .map(|h| h.time)
+            .collect();
+
+        // The hour under way is shown, the ones before it aren't
+        assert_eq!(hours.len(), 24);
+        assert_eq!(hours.first(), Some(&time("2024-06-20T10:00:00Z")));
+        assert_eq!(hours.last(), Some(&time("2024-06-21T09:00:00Z")));
+
+        // Only hourly steps are listed, so the end of the series gives fewer
+        assert_eq!(forecast.next_hours(time("2024-06-21T05:00:00Z"), 24).count(), 9);
+    }
+
+    #[test]
+    fn complete_endpoint_fields_are_read() {
+        let response = complete_fixture();
+        let first = &response.properties.timeseries[0].data;
+        let instant = &first.instant.details;
+        let next_hour = first.next_1_hours.as_ref().and_then(|h| h.details.as_ref()).unwrap();
+
+        assert_eq!(response.properties.meta.units.air_temperature_percentile_10.as_deref(), Some("celsius"));
+        assert_eq!(
+            (instant.air_temperature_percentile_10, instant.air_temperature_percentile_90),
+            (Some(9.2), Some(11.1))
+        );
+        assert_eq!(
+            (instant.wind_speed_percentile_10, instant.wind_speed_percentile_90),
+            (Some(3.1), Some(6.2))
+        );
+        assert_eq!(instant.fog_area_fraction, Some(0.0));
+        assert_eq!(
+            (next_hour.precipitation_amount_min, next_hour.precipitation_amount_max),
+            (Some(0.0), Some(0.3))
+        );
+        assert_eq!(next_hour.probability_of_thunder, Some(1.2));
+
+        // The fixture lies in the past, so its last step is the current one
+        let current = normalize(&response, 59.91, 10.75).unwrap().current;
+        let layers = current.cloud_layers.unwrap();
+        assert_eq!((layers.low, layers.medium, layers.high), (Some(12.5), Some(25.0), Some(40.6)));
+        assert_eq!(current.dew_point, Some(13.8));
+        assert_eq!(current.wind_gust, Some(9.8));
+        assert_eq!(current.uv_index, Some(0.0));
+
+        // The compact endpoint has none of these
+        let current = normalize(&fixture(), 59.91, 10.75).unwrap().current;
+        assert!(current.cloud_layers.is_none());
+        assert_eq!(current.wind_gust, None);
+    }
+}
@@ -0,0 +1,483 @@
+// SPDX-License-Identifier: MIT
+
+//! US National Weather Service (api.weather.gov) backend.
+//!
+//! A location is first resolved to its forecast office grid through
+//! `/points`, which links to the 12 hour and hourly forecasts. Active alerts
+//! for the point come from `/alerts/active`.
+
+use crate::alerts::{Alert, Severity};
+use crate::symbol::{SymbolCode, Variant};
+use super::{
+    aggregate_daily, map_weather_code_to_description, map_weather_code_to_icon, Forecast,
+    HourlyForecast, Period, WeatherData, WeatherError, WeatherProvider, HTTP_CLIENT,
+};
+use chrono::{DateTime, Local};
+use reqwest::header::ACCEPT;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+const BASE_URL: &str = "https://api.weather.gov";
+
+/// Compass points the forecasts give the wind direction in, clockwise from north.
+const WIND_DIRECTIONS: [&str; 16] = [
+    "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW", "W", "WNW", "NW", "NNW",
+];
+
+// NWS API structures
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct PointResponse {
+    pub properties: PointProperties,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PointProperties {
+    pub forecast: String,
+    pub forecast_hourly: String,
+    pub relative_location: Option<RelativeLocation>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct RelativeLocation {
+    pub properties: RelativeLocationProperties,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct RelativeLocationProperties {
+    pub city: String,
+    pub state: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ForecastResponse {
+    pub properties: ForecastProperties,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ForecastProperties {
+    pub periods: Vec<ForecastPeriod>,
+}
+
+/// A period of either forecast: 12 hours (day or night) or a single hour.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ForecastPeriod {
+    pub start_time: DateTime<Local>,
+    pub end_time: DateTime<Local>,
+    pub is_daytime: bool,
+    pub temperature: f64,
+    pub temperature_unit: String,
+    pub probability_of_precipitation: Option<QuantitativeValue>,
+    pub dewpoint: Option<QuantitativeValue>,
+    pub relative_humidity: Option<QuantitativeValue>,
+    pub wind_speed: Option<String>, // e.g. "10 km/h" or "5 to 10 mph"
+    pub wind_direction: Option<String>,
+    pub icon: Option<String>,
+    pub short_forecast: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuantitativeValue {
+    pub unit_code: Option<String>,
+    pub value: Option<f64>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct AlertsResponse {
+    pub features: Vec<AlertFeature>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct AlertFeature {
+    pub geometry: Option<Geometry>,
+    pub properties: AlertProperties,
+}
+
+/// GeoJSON outline, with points as (longitude, latitude).
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum Geometry {
+    Polygon { coordinates: Vec<Vec<(f64, f64)>> },
+    MultiPolygon { coordinates: Vec<Vec<Vec<(f64, f64)>>> },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertProperties {
+    pub event: String,
+    pub headline: Option<String>,
+    pub description: Option<String>,
+    pub severity: Severity,
+    pub onset: Option<DateTime<Local>>,
+    pub expires: Option<DateTime<Local>>,
+    pub ends: Option<DateTime<Local>>,
+    pub area_desc: String,
+}
+
+/// Fetches forecasts from api.weather.gov, which only covers the United States.
+/// No API key is required.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Nws;
+
+impl WeatherProvider for Nws {
+    async fn fetch_forecast(
+        &self,
+        lat: f64,
+        lon: f64,
+    ) -> Result<Forecast, WeatherError> {
+        // More than four decimals get redirected
+        let point_url = format!("{}/points/{:.4},{:.4}", BASE_URL, lat, lon);
+        let point: PointResponse = match get_json(&point_url, &[]).await {
+            // Points outside the US aren't found
+            Err(WeatherError::Http(404)) => return Err(WeatherError::NoData),
+            result => result?,
+        };
+
+        let alerts_url = format!("{}/alerts/active?point={:.4},{:.4}", BASE_URL, lat, lon);
+        let (forecasts, alerts) = futures_util::join!(
+            async {
+                futures_util::try_join!(
+                    get_json::<ForecastResponse>(&point.properties.forecast, &[("units", "si")]),
+                    get_json::<ForecastResponse>(&point.properties.forecast_hourly, &[("units", "si")]),
+                )
+            },
+            get_json::<AlertsResponse>(&alerts_url, &[]),
+        );
+        let (forecast, hourly) = forecasts?;
+
+        normalize(&point, &forecast, &hourly, alerts, lat, lon, Local::now())
+    }
+}
+
+async fn get_json<T: DeserializeOwned>(url: &str, query: &[(&str, &str)]) -> Result<T, WeatherError> {
+    let response = HTTP_CLIENT
+        .get(url)
+        .query(query)
+        .header(ACCEPT, "application/geo+json")
+        .send()
+        .await?;
+
+    if response.status().is_success() {
+        Ok(response.json().await?)
+    } else {
+        Err(WeatherError::from_status(response.status(), response.headers()))
+    }
+}
+
+// Converts the NWS responses into the provider independent model
+fn normalize(
+    point: &PointResponse,
+    forecast: &ForecastResponse,
+    hourly: &ForecastResponse,
+    alerts: Result<AlertsResponse, WeatherError>,
+    lat: f64,
+    lon: f64,
+    now: DateTime<Local>,
+) -> Result<Forecast, WeatherError> {
+    // NWS has no current conditions in the forecast, use the running hour
+    let periods = &hourly.properties.periods;
+    let Some(current) = periods
+        .iter()
+        .find(|period| period.end_time > now)
+        .or(periods.last())
+    else {
+        return Err(WeatherError::NoData);
+    };
+
+    let temperature = celsius(current);
+    let humidity = current.relative_humidity.as_ref().and_then(|h| h.value);
+    let wind = current.wind_speed.as_deref().and_then(wind_speed);
+    let feels_like = match (humidity, wind) {
+        (Some(humidity), Some(wind)) => crate::meteo::feels_like(temperature, humidity, wind),
+        _ => temperature,
+    };
+    let code = symbol_code(current).to_string();
+
+    let current = WeatherData {
+        temperature,
+        feels_like,
+        humidity: humidity.map(|humidity| humidity as u8),
+        description: map_weather_code_to_description(&code),
+        icon: map_weather_code_to_icon(&code),
+        uv_index: None,
+        dew_point: current.dewpoint.as_ref().and_then(|d| d.value),
+        wind_speed: wind,
+        wind_direction: current.wind_direction.as_deref().and_then(wind_direction),
+        wind_gust: None,
+        pressure: None,
+        pressure_trend: None,
+        cloud_layers: None,
+        location: point
+            .properties
+            .relative_location
+            .as_ref()
+            .map(|l| format!("{}, {}", l.properties.city, l.properties.state))
+            .unwrap_or_else(|| format!("({}, {})", lat, lon)),
+        timestamp: std::time::SystemTime::now(),
+    };
+
+    let hourly = periods
+        .iter()
+        .map(|period| {
+            let code = symbol_code(period).to_string();
+            HourlyForecast {
+                time: period.start_time,
+                temperature: celsius(period),
+                description: map_weather_code_to_description(&code),
+                icon: map_weather_code_to_icon(&code),
+                // Only chances are forecast, not amounts
+                precipitation: 0.0,
+                precipitation_probability: period
+                    .probability_of_precipitation
+                    .as_ref()
+                    .and_then(|p| p.value)
+                    .map(|p| p.round() as u8),
+                wind_speed: period.wind_speed.as_deref().and_then(wind_speed).unwrap_or(0.0),
+            }
+        })
+        .collect();
+
+    // Days from the day and night periods of the 12 hour forecast
+    let codes: Vec<String> = forecast
+        .properties
+        .periods
+        .iter()
+        .map(|period| symbol_code(period).to_string())
+        .collect();
+    let periods: Vec<Period> = forecast
+        .properties
+        .periods
+        .iter()
+        .zip(&codes)
+        .map(|(period, code)| Period {
+            start: period.start_time,
+            hours: (period.end_time - period.start_time).num_hours().max(1) as u32,
+            temperature_min: celsius(period),
+            temperature_max: celsius(period),
+            symbol_code: code,
+            precipitation: 0.0,
+        })
+        .collect();
+    let daily = aggregate_daily(&periods);
+
+    // Alerts come from their own endpoint, the forecast is still worth showing without them
+    let alerts = alerts.unwrap_or_else(|err| {
+        eprintln!("Error fetching weather alerts: {}", err);
+        AlertsResponse { features: Vec::new() }
+    });
+    let mut alerts: Vec<Alert> = alerts
+        .features
+        .iter()
+        .map(to_alert)
+        .filter(|alert| !alert.is_expired(now))
+        .collect();
+    alerts.sort_by_key(|alert| std::cmp::Reverse(alert.severity.rank()));
+
+    Ok(Forecast {
+        current,
+        hourly,
+        daily,
+        expires: None,
+        alerts,
+    })
+}
+
+fn to_alert(feature: &AlertFeature) -> Alert {
+    let properties = &feature.properties;
+    // GeoJSON lists longitude first
+    let ring = |ring: &Vec<(f64, f64)>| ring.iter().map(|&(lon, lat)| (lat, lon)).collect();
+    let polygons = match &feature.geometry {
+        Some(Geometry::Polygon { coordinates }) => coordinates.iter().map(ring).collect(),
+        Some(Geometry::MultiPolygon { coordinates }) => coordinates.iter().flatten().map(ring).collect(),
+        Some(Geometry::Other) | None => Vec::new(),
+    };
+
+    Alert {
+        event: properties.event.clone(),
+        headline: properties.headline.clone().unwrap_or_else(|| properties.event.clone()),
+        description: properties.description.clone().unwrap_or_default(),
+        severity: properties.severity,
+        onset: properties.onset,
+        // `expires` is about the message, `ends` about the weather
+        expires: properties.ends.or(properties.expires),
+        area: properties.area_desc.clone(),
+        polygons,
+    }
+}
+
+fn celsius(period: &ForecastPeriod) -> f64 {
+    match period.temperature_unit.as_str() {
+        "F" => (period.temperature - 32.0) * 5.0 / 9.0,
+        _ => period.temperature,
+    }
+}
+
+// Speed in m/s from e.g. "15 km/h", taking the middle of ranges like "5 to 10 mph"
+fn wind_speed(text: &str) -> Option<f64> {
+    let numbers: Vec<f64> = text
+        .split_whitespace()
+        .filter_map(|word| word.parse().ok())
+        .collect();
+    if numbers.is_empty() {
+        return None;
+    }
+
+    let speed = numbers.iter().sum::<f64>() / numbers.len() as f64;
+    if text.ends_with("mph") {
+        Some(speed * 0.44704)
+    } else {
+        Some(speed / 3.6)
+    }
+}
+
+// Degrees the wind comes from, from a compass point like "SW"
+fn wind_direction(text: &str) -> Option<f64> {
+    let index = WIND_DIRECTIONS.iter().position(|point| *point == text)?;
+    Some(index as f64 * 22.5)
+}
+
+// Maps the first condition of an icon URL such as
+// ".../icons/land/night/rain_showers,40/tsra_hi,60?size=small" to a MET Norway symbol
+fn symbol_code(period: &ForecastPeriod) -> SymbolCode {
+    let variant = if period.is_daytime { Variant::Day } else { Variant::Night };
+    let condition = period
+        .icon
+        .as_deref()
+        .and_then(|icon| icon.split('?').next())
+        .and_then(|path| path.split("/icons/land/").nth(1))
+        .and_then(|path| path.split('/').nth(1))
+        .and_then(|condition| condition.split(',').next())
+        .unwrap_or_default();
+
+    match condition {
+        "skc" | "wind_skc" | "hot" | "cold" => SymbolCode::ClearSky(variant),
+        "few" | "wind_few" => SymbolCode::Fair(variant),
+        "sct" | "wind_sct" => SymbolCode::PartlyCloudy(variant),
+        "bkn" | "wind_bkn" | "ovc" | "wind_ovc" => SymbolCode::Cloudy,
+        "rain" => SymbolCode::Rain,
+        "rain_showers" | "rain_showers_hi" => SymbolCode::RainShowers(variant),
+        "tsra" | "tsra_sct" | "tsra_hi" => SymbolCode::RainShowersAndThunder(variant),
+        "tornado" | "hurricane" | "tropical_storm" => SymbolCode::HeavyRainAndThunder,
+        "snow" | "blizzard" => SymbolCode::Snow,
+        "rain_snow" | "rain_sleet" | "snow_sleet" | "sleet" | "fzra" | "rain_fzra" | "snow_fzra" => {
+            SymbolCode::Sleet
+        }
+        "fog" | "haze" | "smoke" | "dust" => SymbolCode::Fog,
+        _ => SymbolCode::Cloudy,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    fn fixture<T: DeserializeOwned>(json: &str) -> T {
+        serde_json::from_str(json).expect("fixture should deserialize")
+    }
+
+    fn forecast() -> Forecast {
+        forecast_with_alerts(Ok(fixture(include_str!("../../tests/fixtures/nws_alerts.json"))))
+    }
+
+    fn forecast_with_alerts(alerts: Result<AlertsResponse, WeatherError>) -> Forecast {
+        let now = Utc.with_ymd_and_hms(2024, 3, 12, 21, 30, 0).unwrap().with_timezone(&Local);
+        normalize(
+            &fixture(include_str!("../../tests/fixtures/nws_points.json")),
+            &fixture(include_str!("../../tests/fixtures/nws_forecast.json")),
+            &fixture(include_str!("../../tests/fixtures/nws_forecast_hourly.json")),
+            alerts,
+            39.7456,
+            -97.0892,
+            now,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn current_conditions_come_from_the_running_hour() {
+        let forecast = forecast();
+        let current = &forecast.current;
+
+        assert_eq!(current.temperature, 8.9);
+        assert_eq!(current.humidity, Some(62));
+        assert_eq!(current.dew_point, Some(1.7));
+        assert_eq!(current.wind_speed, Some(5.0));
+        assert_eq!(current.wind_direction, Some(225.0));
+        assert_eq!(current.description, "Rain showers");
+        assert_eq!(current.icon, "09d");
+        assert_eq!(current.location, "Linn, KS");
+    }
+
+    #[test]
+    fn hourly_periods_are_normalized() {
+        let forecast = forecast();
+
+        assert_eq!(forecast.hourly.len(), 4);
+        assert_eq!(forecast.hourly[1].precipitation_probability, Some(40));
+        assert_eq!(forecast.hourly[3].icon, "11n");
+    }
+
+    #[test]
+    fn days_combine_day_and_night_periods() {
+        let forecast = forecast();
+
+        // Periods are grouped by the local date they start on, which
+        // depends on the timezone the tests run in
+        let periods = [
+            ("2024-03-12T06:00:00-05:00", 12.0),
+            ("2024-03-12T18:00:00-05:00", -1.0),
+            ("2024-03-13T06:00:00-05:00", 4.0),
+            ("2024-03-13T18:00:00-05:00", -4.0),
+        ];
+        let local_date = |time: &str| DateTime::parse_from_rfc3339(time).unwrap().with_timezone(&Local).date_naive();
+        let mut dates: Vec<NaiveDate> = periods.iter().map(|(start, _)| local_date(start)).collect();
+        dates.dedup();
+
+        assert_eq!(forecast.daily.iter().map(|d| d.date).collect::<Vec<_>>(), dates);
+        for day in &forecast.daily {
+            let temperatures: Vec<f64> = periods
+                .iter()
+                .filter(|(start, _)| local_date(start) == day.date)
+                .map(|(_, temperature)| *temperature)
+                .collect();
+            assert_eq!(day.temperature_min, temperatures.iter().copied().fold(f64::INFINITY, f64::min));
+            assert_eq!(day.temperature_max, temperatures.iter().copied().fold(f64::NEG_INFINITY, f64::max));
+        }
+    }
+
+    #[test]
+    fn active_alerts_are_included() {
+        let forecast = forecast();
+        let alert = &forecast.alerts[0];
+
+        assert_eq!(forecast.alerts.len(), 2);
+        assert_eq!(alert.event, "Winter Storm Warning");
+        assert_eq!(alert.severity, Severity::Severe);
+        assert!(alert.covers(39.75, -97.09));
+        assert_eq!(forecast.alerts[1].severity, Severity::Minor);
+        assert!(forecast.alerts[1].polygons.is_empty());
+    }
+
+    #[test]
+    fn forecast_is_kept_when_alerts_fail() {
+        let forecast = forecast_with_alerts(Err(WeatherError::Server(503)));
+
+        assert!(forecast.alerts.is_empty());
+        assert_eq!(forecast.current.temperature, 8.9);
+        assert!(!forecast.daily.is_empty());
+    }
+
+    #[test]
+    fn wind_texts_are_parsed() {
+        assert_eq!(wind_speed("18 km/h"), Some(5.0));
+        assert_eq!(wind_speed("5 to 15 mph"), Some(10.0 * 0.44704));
+        assert_eq!(wind_speed("calm"), None);
+        assert_eq!(wind_direction("NNW"), Some(337.5));
+        assert_eq!(wind_direction("?"), None);
+    }
+}
@@ -0,0 +1,296 @@
+// SPDX-License-Identifier: MIT
+
+//! Open-Meteo forecast backend.
+
+use super::{
+    map_weather_code_to_description, map_weather_code_to_icon, DailyForecast, Forecast,
+    HourlyForecast, WeatherData, WeatherError, WeatherProvider, HTTP_CLIENT,
+};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone};
+use serde::{Deserialize, Serialize};
+
+const CURRENT_FIELDS: &str =
+    "temperature_2m,relative_humidity_2m,apparent_temperature,is_day,weather_code,wind_speed_10m,wind_direction_10m,uv_index,pressure_msl";
+const HOURLY_FIELDS: &str =
+    "temperature_2m,weather_code,precipitation,precipitation_probability,wind_speed_10m,is_day";
+const DAILY_FIELDS: &str =
+    "weather_code,temperature_2m_max,temperature_2m_min,precipitation_sum,precipitation_probability_max";
+
+// Open-Meteo API structures
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Current {
+    pub time: String,
+    pub temperature_2m: Option<f64>,
+    pub relative_humidity_2m: Option<f64>,
+    pub apparent_temperature: Option<f64>,
+    pub is_day: Option<u8>,
+    pub weather_code: Option<u8>,
+    pub wind_speed_10m: Option<f64>,
+    pub wind_direction_10m: Option<f64>,
+    pub uv_index: Option<f64>,
+    pub pressure_msl: Option<f64>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Hourly {
+    pub time: Vec<String>,
+    pub temperature_2m: Vec<Option<f64>>,
+    pub weather_code: Vec<Option<u8>>,
+    pub precipitation: Vec<Option<f64>>,
+    pub precipitation_probability: Vec<Option<u8>>,
+    pub wind_speed_10m: Vec<Option<f64>>,
+    pub is_day: Vec<Option<u8>>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Daily {
+    pub time: Vec<NaiveDate>,
+    pub weather_code: Vec<Option<u8>>,
+    pub temperature_2m_max: Vec<Option<f64>>,
+    pub temperature_2m_min: Vec<Option<f64>>,
+    pub precipitation_sum: Vec<Option<f64>>,
+    pub precipitation_probability_max: Vec<Option<u8>>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct OpenMeteoResponse {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub utc_offset_seconds: i32,
+    pub timezone: String,
+    pub current: Current,
+    pub hourly: Hourly,
+    pub daily: Daily,
+}
+
+/// Fetches forecasts from api.open-meteo.com. No API key is required.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct OpenMeteo;
+
+impl WeatherProvider for OpenMeteo {
+    async fn fetch_forecast(
+        &self,
+        lat: f64,
+        lon: f64,
+    ) -> Result<Forecast, WeatherError> {
+        let url = format!(
+            "https://api.open-meteo.com/v1/forecast?latitude={}&longitude={}&current={}&hourly={}&daily={}&timezone=auto&wind_speed_unit=ms",
+            lat, lon, CURRENT_FIELDS, HOURLY_FIELDS, DAILY_FIELDS
+        );
+
+        let response = HTTP_CLIENT.get(&url).send().await?;
+
+        if response.status().is_success() {
+            let weather_response: OpenMeteoResponse = response.json().await?;
+            normalize(&weather_response, lat, lon)
+        } else {
+            Err(WeatherError::from_status(response.status(), response.headers()))
+        }
+    }
+}
+
+// Converts an Open-Meteo response into the provider independent model
+fn normalize(
+    response: &OpenMeteoResponse,
+    lat: f64,
+    lon: f64,
+) -> Result<Forecast, WeatherError> {
+    let offset = FixedOffset::east_opt(response.utc_offset_seconds).ok_or_else(|| {
+        WeatherError::Parse(format!("invalid UTC offset {}", response.utc_offset_seconds))
+    })?;
+
+    let current = &response.current;
+    let Some(temperature) = current.temperature_2m else {
+        return Err(WeatherError::NoData);
+    };
+    let now = parse_time(&current.time, offset)?;
+    let code = symbol_code(current.weather_code, current.is_day);
+
+    let current = WeatherData {
+        temperature,
+        feels_like: current.apparent_temperature.unwrap_or(temperature),
+        humidity: current.relative_humidity_2m.map(|humidity| humidity as u8),
+        description: map_weather_code_to_description(code),
+        icon: map_weather_code_to_icon(code),
+        uv_index: current.uv_index,
+        dew_point: None,
+        wind_speed: current.wind_speed_10m,
+        wind_direction: current.wind_direction_10m,
+        wind_gust: None,
+        pressure: current.pressure_msl,
+        pressure_trend: None,
+        cloud_layers: None,
+        location: format!("({}, {})", lat, lon),
+        timestamp: std::time::SystemTime::now(),
+    };
+
+    // The hourly series starts at local midnight, skip the hours already past
+    let hourly_data = &response.hourly;
+    let mut hourly = Vec::with_capacity(hourly_data.time.len());
+    for (i, time) in hourly_data.time.iter().enumerate() {
+        let time = parse_time(time, offset)?;
+        if time + chrono::Duration::hours(1) <= now {
+            continue;
+        }
+        let Some(temperature) = hourly_data.temperature_2m.get(i).copied().flatten() else {
+            continue;
+        };
+        let code = symbol_code(
+            hourly_data.weather_code.get(i).copied().flatten(),
+            hourly_data.is_day.get(i).copied().flatten(),
+        );
+
+        hourly.push(HourlyForecast {
+            time,
+            temperature,
+            description: map_weather_code_to_description(code),
+            icon: map_weather_code_to_icon(code),
+            precipitation: hourly_data.precipitation.get(i).copied().flatten().unwrap_or(0.0),
+            precipitation_probability: hourly_data.precipitation_probability.get(i).copied().flatten(),
+            wind_speed: hourly_data.wind_speed_10m.get(i).copied().flatten().unwrap_or(0.0),
+        });
+    }
+
+    let daily_data = &response.daily;
+    let daily = daily_data
+        .time
+        .iter()
+        .enumerate()
+        .filter_map(|(i, date)| {
+            let code = symbol_code(daily_data.weather_code.get(i).copied().flatten(), Some(1));
+            Some(DailyForecast {
+                date: *date,
+                temperature_min: daily_data.temperature_2m_min.get(i).copied().flatten()?,
+                temperature_max: daily_data.temperature_2m_max.get(i).copied().flatten()?,
+                description: map_weather_code_to_description(code),
+                icon: map_weather_code_to_icon(code),
+                precipitation: daily_data.precipitation_sum.get(i).copied().flatten().unwrap_or(0.0),
+            })
+        })
+        .collect();
+
+    Ok(Forecast {
+        current,
+        hourly,
+        daily,
+        expires: None,
+        alerts: Vec::new(),
+    })
+}
+
+// Open-Meteo reports local wall clock times without an offset
+fn parse_time(
+    time: &str,
+    offset: FixedOffset,
+) -> Result<DateTime<Local>, WeatherError> {
+    let naive = NaiveDateTime::parse_from_str(time, "%Y-%m-%dT%H:%M")?;
+    offset
+        .from_local_datetime(&naive)
+        .single()
+        .map(|time| time.with_timezone(&Local))
+        .ok_or_else(|| WeatherError::Parse(format!("invalid time {}", time)))
+}
+
+// Maps a WMO weather interpretation code to the equivalent MET Norway symbol code
+fn symbol_code(weather_code: Option<u8>, is_day: Option<u8>) -> &'static str {
+    let day = is_day != Some(0);
+    let variant = |day_code, night_code| if day { day_code } else { night_code };
+
+    match weather_code {
+        Some(0) => variant("clearsky_day", "clearsky_night"),
+        Some(1) => variant("fair_day", "fair_night"),
+        Some(2) => variant("partlycloudy_day", "partlycloudy_night"),
+        Some(3) => "cloudy",
+        Some(45 | 48) => "fog",
+        Some(51 | 53 | 61) => "lightrain",
+        Some(55 | 63) => "rain",
+        Some(65) => "heavyrain",
+        Some(56 | 66) => "lightsleet",
+        Some(57 | 67) => "sleet",
+        Some(71 | 77) => "lightsnow",
+        Some(73) => "snow",
+        Some(75) => "heavysnow",
+        Some(80) => variant("lightrainshowers_day", "lightrainshowers_night"),
+        Some(81) => variant("rainshowers_day", "rainshowers_night"),
+        Some(82) => variant("heavyrainshowers_day", "heavyrainshowers_night"),
+        Some(85) => variant("lightsnowshowers_day", "lightsnowshowers_night"),
+        Some(86) => variant("heavysnowshowers_day", "heavysnowshowers_night"),
+        Some(95) => "rainandthunder",
+        Some(96 | 99) => "heavyrainandthunder",
+        _ => variant("clearsky_day", "clearsky_night"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn fixture() -> OpenMeteoResponse {
+        serde_json::from_str(include_str!("../../tests/fixtures/open_meteo_forecast.json"))
+            .expect("fixture should deserialize")
+    }
+
+    #[test]
+    fn current_conditions_are_normalized() {
+        let forecast = normalize(&fixture(), 59.91, 10.75).unwrap();
+
+        assert_eq!(forecast.current.temperature, 6.4);
+        assert_eq!(forecast.current.feels_like, 3.1);
+        assert_eq!(forecast.current.humidity, Some(71));
+        assert_eq!(forecast.current.uv_index, Some(1.35));
+        assert_eq!(forecast.current.wind_speed, Some(4.2));
+        assert_eq!(forecast.current.pressure, Some(1008.7));
+        assert_eq!(forecast.current.wind_direction, Some(225.0));
+        assert_eq!(forecast.current.description, "Cloudy");
+        assert_eq!(forecast.current.icon, "03d");
+    }
+
+    #[test]
+    fn hourly_series_starts_at_current_hour() {
+        let forecast = normalize(&fixture(), 59.91, 10.75).unwrap();
+        let first = &forecast.hourly[0];
+
+        assert_eq!(forecast.hourly.len(), 5);
+        assert_eq!(
+            first.time.with_timezone(&Utc).to_rfc3339(),
+            "2024-03-12T13:00:00+00:00"
+        );
+        assert_eq!(first.temperature, 6.4);
+        assert_eq!(first.precipitation_probability, Some(10));
+
+        let rain = &forecast.hourly[3];
+        assert_eq!(rain.precipitation, 1.2);
+        assert_eq!(rain.precipitation_probability, Some(80));
+        assert_eq!(rain.icon, "10d");
+    }
+
+    #[test]
+    fn night_hours_use_night_variants() {
+        let forecast = normalize(&fixture(), 59.91, 10.75).unwrap();
+        let night = forecast.hourly.last().unwrap();
+
+        assert_eq!(night.description, "Light rain showers");
+        assert_eq!(night.icon, "09n");
+    }
+
+    #[test]
+    fn daily_summaries_are_taken_as_reported() {
+        let forecast = normalize(&fixture(), 59.91, 10.75).unwrap();
+
+        assert_eq!(forecast.daily.len(), 2);
+        assert_eq!(forecast.daily[0].date, NaiveDate::from_ymd_opt(2024, 3, 12).unwrap());
+        assert_eq!(forecast.daily[0].temperature_min, 1.8);
+        assert_eq!(forecast.daily[0].temperature_max, 7.9);
+        assert_eq!(forecast.daily[0].precipitation, 4.6);
+        assert_eq!(forecast.daily[1].description, "Snow");
+    }
+
+    #[test]
+    fn wmo_codes_map_to_met_symbols() {
+        assert_eq!(symbol_code(Some(0), Some(0)), "clearsky_night");
+        assert_eq!(symbol_code(Some(45), Some(1)), "fog");
+        assert_eq!(symbol_code(Some(82), Some(1)), "heavyrainshowers_day");
+        assert_eq!(symbol_code(Some(99), Some(0)), "heavyrainandthunder");
+    }
+}
@@ -0,0 +1,373 @@
+// SPDX-License-Identifier: MIT
+
+//! OpenWeatherMap One Call backend, which needs an API key.
+
+use crate::alerts::{Alert, Severity};
+use crate::symbol::{SymbolCode, Variant};
+use super::{
+    map_weather_code_to_description, map_weather_code_to_icon, DailyForecast, Forecast,
+    HourlyForecast, WeatherData, WeatherError, WeatherProvider, HTTP_CLIENT,
+};
+use chrono::{DateTime, FixedOffset, Local};
+use serde::{Deserialize, Serialize};
+
+const ONE_CALL_URL: &str = "https://api.openweathermap.org/data/3.0/onecall";
+
+// OpenWeatherMap API structures, requested in metric units (°C, m/s, hPa, mm)
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct OneCallResponse {
+    pub timezone_offset: i32,
+    pub current: Current,
+    #[serde(default)]
+    pub hourly: Vec<Hourly>,
+    #[serde(default)]
+    pub daily: Vec<Daily>,
+    #[serde(default)]
+    pub alerts: Vec<OwmAlert>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Current {
+    pub dt: i64,
+    pub temp: f64,
+    pub feels_like: f64,
+    pub pressure: f64,
+    pub humidity: f64,
+    pub dew_point: Option<f64>,
+    pub uvi: Option<f64>,
+    pub wind_speed: Option<f64>,
+    pub wind_deg: Option<f64>,
+    pub wind_gust: Option<f64>,
+    #[serde(default)]
+    pub weather: Vec<Condition>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Hourly {
+    pub dt: i64,
+    pub temp: f64,
+    pub wind_speed: Option<f64>,
+    pub pop: Option<f64>, // probability of precipitation, 0 to 1
+    pub rain: Option<LastHour>,
+    pub snow: Option<LastHour>,
+    #[serde(default)]
+    pub weather: Vec<Condition>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct LastHour {
+    #[serde(rename = "1h")]
+    pub one_hour: f64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Daily {
+    pub dt: i64,
+    pub temp: DailyTemperature,
+    pub rain: Option<f64>,
+    pub snow: Option<f64>,
+    #[serde(default)]
+    pub weather: Vec<Condition>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct DailyTemperature {
+    pub min: f64,
+    pub max: f64,
+}
+
+/// A weather condition, with its id and an icon such as `10n`.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Condition {
+    pub id: u16,
+    pub icon: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct OwmAlert {
+    pub sender_name: String,
+    pub event: String,
+    pub start: i64,
+    pub end: i64,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// Fetches forecasts from OpenWeatherMap's One Call API 3.0 with the
+/// user's API key.
+#[derive(Debug, Clone)]
+pub struct OpenWeatherMap {
+    pub api_key: String,
+}
+
+impl WeatherProvider for OpenWeatherMap {
+    async fn fetch_forecast(
+        &self,
+        lat: f64,
+        lon: f64,
+    ) -> Result<Forecast, WeatherError> {
+        self.fetch_from(ONE_CALL_URL, lat, lon).await
+    }
+}
+
+impl OpenWeatherMap {
+    // Takes the endpoint so tests can point it at a local address
+    async fn fetch_from(&self, url: &str, lat: f64, lon: f64) -> Result<Forecast, WeatherError> {
+        let lat_param = lat.to_string();
+        let lon_param = lon.to_string();
+        let query = [
+            ("lat", lat_param.as_str()),
+            ("lon", lon_param.as_str()),
+            ("appid", self.api_key.as_str()),
+            ("units", "metric"),
+            ("exclude", "minutely"),
+        ];
+
+        let response = HTTP_CLIENT.get(url).query(&query).send().await?;
+
+        if response.status().is_success() {
+            let one_call: OneCallResponse = response.json().await?;
+            normalize(&one_call, lat, lon)
+        } else {
+            Err(WeatherError::from_status(response.status(), response.headers()))
+        }
+    }
+}
+
+// Converts a One Call response into the provider independent model
+fn normalize(
+    response: &OneCallResponse,
+    lat: f64,
+    lon: f64,
+) -> Result<Forecast, WeatherError> {
+    let offset = FixedOffset::east_opt(response.timezone_offset).ok_or_else(|| {
+        WeatherError::Parse(format!("invalid UTC offset {}", response.timezone_offset))
+    })?;
+
+    let current = &response.current;
+    let code = symbol_code(current.weather.first()).to_string();
+
+    let current = WeatherData {
+        temperature: current.temp,
+        feels_like: current.feels_like,
+        humidity: Some(current.humidity as u8),
+        description: map_weather_code_to_description(&code),
+        icon: map_weather_code_to_icon(&code),
+        uv_index: current.uvi,
+        dew_point: current.dew_point,
+        wind_speed: current.wind_speed,
+        wind_direction: current.wind_deg,
+        wind_gust: current.wind_gust,
+        pressure: Some(current.pressure),
+        pressure_trend: None,
+        cloud_layers: None,
+        location: format!("({}, {})", lat, lon),
+        timestamp: std::time::SystemTime::now(),
+    };
+
+    let hourly = response
+        .hourly
+        .iter()
+        .map(|hour| {
+            let code = symbol_code(hour.weather.first()).to_string();
+            let precipitation = hour.rain.as_ref().map_or(0.0, |rain| rain.one_hour)
+                + hour.snow.as_ref().map_or(0.0, |snow| snow.one_hour);
+            Ok(HourlyForecast {
+                time: local_time(hour.dt)?,
+                temperature: hour.temp,
+                description: map_weather_code_to_description(&code),
+                icon: map_weather_code_to_icon(&code),
+                precipitation,
+                precipitation_probability: hour.pop.map(|pop| (pop * 100.0).round() as u8),
+                wind_speed: hour.wind_speed.unwrap_or(0.0),
+            })
+        })
+        .collect::<Result<Vec<_>, WeatherError>>()?;
+
+    // Days are those of the location, whose noon the timestamps point at
+    let daily = response
+        .daily
+        .iter()
+        .map(|day| {
+            let code = symbol_code(day.weather.first()).to_string();
+            Ok(DailyForecast {
+                date: local_time(day.dt)?.with_timezone(&offset).date_naive(),
+                temperature_min: day.temp.min,
+                temperature_max: day.temp.max,
+                description: map_weather_code_to_description(&code),
+                icon: map_weather_code_to_icon(&code),
+                precipitation: day.rain.unwrap_or(0.0) + day.snow.unwrap_or(0.0),
+            })
+        })
+        .collect::<Result<Vec<_>, WeatherError>>()?;
+
+    let alerts = response
+        .alerts
+        .iter()
+        .map(to_alert)
+        .collect::<Result<Vec<_>, WeatherError>>()?;
+
+    Ok(Forecast {
+        current,
+        hourly,
+        daily,
+        expires: None,
+        alerts,
+    })
+}
+
+fn local_time(timestamp: i64) -> Result<DateTime<Local>, WeatherError> {
+    DateTime::from_timestamp(timestamp, 0)
+        .map(|time| time.with_timezone(&Local))
+        .ok_or_else(|| WeatherError::Parse(format!("invalid timestamp {}", timestamp)))
+}
+
+// One Call alerts come from national agencies for the requested point, but
+// without a severity or an area outline
+fn to_alert(alert: &OwmAlert) -> Result<Alert, WeatherError> {
+    Ok(Alert {
+        event: alert.event.clone(),
+        headline: alert.event.clone(),
+        description: alert.description.clone(),
+        severity: Severity::Unknown,
+        onset: Some(local_time(alert.start)?),
+        expires: Some(local_time(alert.end)?),
+        area: alert.sender_name.clone(),
+        polygons: Vec::new(),
+    })
+}
+
+// Maps an OpenWeatherMap condition id to a MET Norway symbol, taking day or
+// night from the icon.
+fn symbol_code(condition: Option<&Condition>) -> SymbolCode {
+    let variant = match condition {
+        Some(condition) if condition.icon.ends_with('n') => Variant::Night,
+        _ => Variant::Day,
+    };
+
+    match condition.map(|condition| condition.id) {
+        Some(200 | 210 | 230 | 231) => SymbolCode::LightRainAndThunder,
+        Some(201 | 211 | 221) => SymbolCode::RainAndThunder,
+        Some(202 | 212 | 232) => SymbolCode::HeavyRainAndThunder,
+        Some(300 | 301 | 310 | 500) => SymbolCode::LightRain,
+        Some(302 | 311 | 312 | 501) => SymbolCode::Rain,
+        Some(313 | 314 | 321) => SymbolCode::RainShowers(variant),
+        Some(502..=504) => SymbolCode::HeavyRain,
+        Some(511 | 615) => SymbolCode::LightSleet,
+        Some(611 | 616) => SymbolCode::Sleet,
+        Some(612) => SymbolCode::LightSleetShowers(variant),
+        Some(613) => SymbolCode::SleetShowers(variant),
+        Some(520) => SymbolCode::LightRainShowers(variant),
+        Some(521 | 531) => SymbolCode::RainShowers(variant),
+        Some(522) => SymbolCode::HeavyRainShowers(variant),
+        Some(600) => SymbolCode::LightSnow,
+        Some(601) => SymbolCode::Snow,
+        Some(602) => SymbolCode::HeavySnow,
+        Some(620) => SymbolCode::LightSnowShowers(variant),
+        Some(621) => SymbolCode::SnowShowers(variant),
+        Some(622) => SymbolCode::HeavySnowShowers(variant),
+        Some(701..=799) => SymbolCode::Fog,
+        Some(801) => SymbolCode::Fair(variant),
+        Some(802) => SymbolCode::PartlyCloudy(variant),
+        Some(803 | 804) => SymbolCode::Cloudy,
+        _ => SymbolCode::ClearSky(variant),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn forecast() -> Forecast {
+        let response: OneCallResponse =
+            serde_json::from_str(include_str!("../../tests/fixtures/openweathermap_onecall.json"))
+                .expect("fixture should deserialize");
+        normalize(&response, 52.52, 13.405).unwrap()
+    }
+
+    #[test]
+    fn current_conditions_are_normalized() {
+        let current = forecast().current;
+
+        assert_eq!(current.temperature, 7.2);
+        assert_eq!(current.feels_like, 4.9);
+        assert_eq!(current.humidity, Some(81));
+        assert_eq!(current.dew_point, Some(4.1));
+        assert_eq!(current.pressure, Some(1012.0));
+        assert_eq!(current.uv_index, Some(0.8));
+        assert_eq!(current.wind_speed, Some(5.1));
+        assert_eq!(current.wind_gust, Some(9.8));
+        assert_eq!(current.wind_direction, Some(240.0));
+        assert_eq!(current.description, "Partly cloudy");
+        assert_eq!(current.icon, "02d");
+    }
+
+    #[test]
+    fn hourly_conditions_map_to_icon_codes() {
+        let forecast = forecast();
+        let icons: Vec<&str> = forecast.hourly.iter().map(|h| h.icon.as_str()).collect();
+
+        assert_eq!(icons, ["02d", "10d", "09n", "11d", "13d"]);
+        assert_eq!(
+            forecast.hourly[0].time,
+            DateTime::from_timestamp(1710252000, 0).unwrap().with_timezone(&Local)
+        );
+        assert_eq!(forecast.hourly[1].precipitation, 1.6);
+        assert_eq!(forecast.hourly[1].precipitation_probability, Some(70));
+        assert_eq!(forecast.hourly[1].wind_speed, 4.0);
+        assert_eq!(forecast.hourly[4].precipitation, 0.5);
+    }
+
+    #[test]
+    fn days_use_the_dates_of_the_location() {
+        let forecast = forecast();
+        let first = &forecast.daily[0];
+
+        assert_eq!(forecast.daily.len(), 2);
+        assert_eq!(first.date, NaiveDate::from_ymd_opt(2024, 3, 12).unwrap());
+        assert_eq!(first.temperature_min, 3.9);
+        assert_eq!(first.temperature_max, 9.4);
+        assert_eq!(first.precipitation, 2.4);
+        assert_eq!(first.icon, "10d");
+        assert_eq!(forecast.daily[1].precipitation, 0.3 + 1.2);
+    }
+
+    #[test]
+    fn alerts_are_kept() {
+        let alerts = forecast().alerts;
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].event, "Strong wind gusts");
+        assert_eq!(alerts[0].area, "Deutscher Wetterdienst");
+        assert_eq!(alerts[0].severity, Severity::Unknown);
+        assert!(alerts[0].expires > alerts[0].onset);
+    }
+
+    #[test]
+    fn condition_ids_map_to_symbols() {
+        let condition = |id, icon: &str| Condition { id, icon: icon.to_string() };
+
+        assert_eq!(symbol_code(Some(&condition(800, "01n"))), SymbolCode::ClearSky(Variant::Night));
+        assert_eq!(symbol_code(Some(&condition(521, "09d"))), SymbolCode::RainShowers(Variant::Day));
+        assert_eq!(symbol_code(Some(&condition(741, "50d"))), SymbolCode::Fog);
+        assert_eq!(symbol_code(Some(&condition(804, "04n"))), SymbolCode::Cloudy);
+        assert_eq!(symbol_code(None), SymbolCode::ClearSky(Variant::Day));
+    }
+
+    #[tokio::test]
+    async fn network_errors_do_not_reveal_the_api_key() {
+        let provider = OpenWeatherMap {
+            api_key: "0123456789abcdef".to_string(),
+        };
+
+        // Nothing listens on the discard port
+        let error = provider
+            .fetch_from("http://127.0.0.1:9/data/3.0/onecall", 52.52, 13.405)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, WeatherError::Network(_)), "{:?}", error);
+        assert!(!format!("{:?} {}", error, error).contains("0123456789abcdef"));
+    }
+}